@@ -29,10 +29,10 @@ jq ".version = \"$MAJOR_MINOR_VERSION.0\"" packages/typescript/package.json > te
 mv temp.json packages/typescript/package.json
 echo "export const schemaVersion = \"$MAJOR_MINOR_VERSION\";" > packages/typescript/src/version.ts
 
-# Update Rust Cargo.toml
-echo "Updating Rust package version..."
-sed -i.bak "s/^version = \".*\"/version = \"$MAJOR_MINOR_VERSION.0\"/" packages/rust/Cargo.toml
-rm packages/rust/Cargo.toml.bak
+# Update Rust schema version, the crate is versioned separately since 0.8.0
+echo "Updating Rust schema version..."
+sed -i.bak "s/^pub(crate) const SCHEMA_VERSION: &str = \".*\";/pub(crate) const SCHEMA_VERSION: \&str = \"${MAJOR_MINOR_VERSION/./_}\";/" packages/rust/src/version.rs
+rm packages/rust/src/version.rs.bak
 
 # Create/Update Go version file
 echo "Updating Go version..."
@@ -47,6 +47,9 @@ npx quicktype -s schema sample/Network.json --lang typescript --top-level Networ
 
 echo "Generating Rust types..."
 npx quicktype -s schema sample/Network.json --lang rust --top-level NetworksRegistry --density normal --visibility public --derive-debug --derive-clone --out packages/rust/src/types.rs
+# quicktype can't generate unknown field maps, catch-all enum variants and private fields, add them
+# new enum values also need to be listed in packages/rust/src/enums.rs
+node packages/rust/scripts/patch-types.js packages/rust/src/types.rs
 
 echo "Generating Go types..."
 npx quicktype -s schema sample/Network.json --lang go --top-level NetworksRegistry --package registry --out packages/golang/lib/types.go
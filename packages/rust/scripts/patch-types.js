@@ -0,0 +1,76 @@
+#!/usr/bin/env node
+
+// Adds what quicktype can't generate to src/types.rs, run by generate.sh after quicktype:
+// - unset optional fields are skipped when serializing, like in the published registry
+// - every struct keeps unknown fields in an `extra` map
+// - every enum gets an `Unknown(String)` variant for values added to the schema later,
+//   with string conversions and serde implemented in src/enums.rs
+// - the registry gets its private lookup index and key order
+//
+// usage: node scripts/patch-types.js src/types.rs
+
+const fs = require("fs");
+
+const file = process.argv[2];
+const lines = fs.readFileSync(file, "utf8").split("\n");
+
+const EXTRA = [
+  "",
+  "    /// Fields not known to this version of the crate, kept so they serialize back unchanged",
+  "    #[serde(flatten)]",
+  "    pub extra: serde_json::Map<String, serde_json::Value>,",
+];
+
+const REGISTRY_FIELDS = [
+  "",
+  "    /// Lookup index over `networks`, built on first use",
+  "    #[serde(skip)]",
+  "    pub(crate) index: crate::index::LazyIndex,",
+  "",
+  "    /// Order of the keys as read, used when serializing back to JSON",
+  "    #[serde(skip)]",
+  "    pub(crate) key_order: crate::key_order::KeyOrder,",
+];
+
+const UNKNOWN = [
+  "",
+  "    /// Value added to the schema after this version of the crate, kept as is",
+  "    Unknown(String),",
+];
+
+const out = [];
+let item = null;
+
+for (let i = 0; i < lines.length; i++) {
+  const line = lines[i];
+
+  // enums implement serde by hand, see src/enums.rs
+  if (line === "#[derive(Debug, Clone, Serialize, Deserialize)]" && /^pub enum /.test(lines[i + 2] || "")) {
+    out.push("#[derive(Debug, Clone, PartialEq, Eq, Hash)]");
+    i++; // #[serde(rename_all = ...)]
+    continue;
+  }
+
+  const start = line.match(/^pub (struct|enum) (\w+) \{$/);
+  if (start) {
+    item = { kind: start[1], name: start[2] };
+  } else if (item && line === "}") {
+    if (item.kind === "struct") {
+      out.push(...EXTRA);
+      if (item.name === "NetworksRegistry") {
+        out.push(...REGISTRY_FIELDS);
+      }
+    } else {
+      out.push(...UNKNOWN);
+    }
+    item = null;
+  } else if (item && item.kind === "enum" && /^    #\[serde\(rename = ".*"\)\]$/.test(line)) {
+    continue;
+  } else if (item && item.kind === "struct" && /^    pub \w+: Option</.test(line)) {
+    out.push('    #[serde(skip_serializing_if = "Option::is_none")]');
+  }
+
+  out.push(line);
+}
+
+fs.writeFileSync(file, out.join("\n"));
@@ -0,0 +1,315 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Parser, Subcommand, ValueEnum};
+use graph_networks_registry::{
+    Error, Network, NetworkType, NetworksRegistry, Protocol, RegistryClient, RegistryVersion, RegistryVersionNumber, Service,
+};
+use serde::Serialize;
+
+/// Query The Graph Networks Registry
+#[derive(Debug, Parser)]
+#[command(name = "graph-networks", version)]
+struct Cli {
+    /// Load the registry from a local file
+    #[arg(long, global = true, conflicts_with_all = ["url", "registry_version"])]
+    file: Option<PathBuf>,
+
+    /// Load the registry from a URL
+    #[arg(long, global = true, conflicts_with = "registry_version")]
+    url: Option<String>,
+
+    /// Published registry version to fetch, e.g. 0.7.0 [default: latest]
+    #[arg(long, global = true, value_parser = parse_registry_version)]
+    registry_version: Option<VersionArg>,
+
+    /// Output format
+    #[arg(long, short, global = true, value_enum, default_value_t = Output::Table)]
+    output: Output,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Show a network by graph id (id or alias) or CAIP-2 chain id
+    Get { id: String },
+
+    /// List networks, optionally filtered
+    List {
+        /// Only networks of this type, can be repeated
+        #[arg(long = "type", value_parser = known_values::<NetworkType>(NetworkType::VALUES))]
+        network_type: Vec<NetworkType>,
+
+        /// Only networks with this graph-node protocol, can be repeated
+        #[arg(long, value_parser = known_values::<Protocol>(Protocol::VALUES))]
+        protocol: Vec<Protocol>,
+
+        /// Only networks providing this service, can be repeated
+        #[arg(long, value_parser = parse_service)]
+        service: Vec<Service>,
+    },
+
+    /// List the aliases of every network
+    Aliases,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VersionArg {
+    Latest,
+    Number(RegistryVersionNumber),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    Table,
+    Json,
+    Yaml,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let registry = load(&cli).await?;
+    let output = render(&cli, &registry)?;
+
+    // a closed pipe, e.g. `graph-networks list | head`, is not an error
+    let mut stdout = io::stdout().lock();
+    match stdout.write_all(output.as_bytes()).and_then(|()| stdout.flush()) {
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+fn render(cli: &Cli, registry: &NetworksRegistry) -> Result<String, Box<dyn std::error::Error>> {
+    let output = match &cli.command {
+        Command::Get { id } => {
+            let network = registry
+                .get_network_by_graph_id(id)
+                .or_else(|| registry.get_network_by_caip2_id(id))
+                .ok_or_else(|| format!("network \"{}\" not found", id))?;
+            match cli.output {
+                Output::Table => network_details(network),
+                output => serialized(output, network)?,
+            }
+        }
+        Command::List {
+            network_type,
+            protocol,
+            service,
+        } => {
+            let mut query = registry.query();
+            for network_type in network_type {
+                query = query.network_type(network_type.clone());
+            }
+            for protocol in protocol {
+                query = query.protocol(protocol.clone());
+            }
+            for &service in service {
+                query = query.service(service);
+            }
+            let networks: Vec<&Network> = query.into_iter().collect();
+            match cli.output {
+                Output::Table => {
+                    let rows = networks
+                        .iter()
+                        .map(|network| {
+                            vec![
+                                network.id.clone(),
+                                network.full_name.clone(),
+                                network.network_type.to_string(),
+                                network.caip2_id.clone(),
+                            ]
+                        })
+                        .collect();
+                    table(&["ID", "NAME", "TYPE", "CAIP-2"], rows)
+                }
+                output => serialized(output, &networks)?,
+            }
+        }
+        Command::Aliases => {
+            let aliases: BTreeMap<&str, &[String]> = registry
+                .networks
+                .iter()
+                .map(|network| (network.id.as_str(), network.aliases.as_deref().unwrap_or_default()))
+                .collect();
+            match cli.output {
+                Output::Table => {
+                    let rows = aliases
+                        .iter()
+                        .map(|(id, aliases)| vec![id.to_string(), aliases.join(", ")])
+                        .collect();
+                    table(&["ID", "ALIASES"], rows)
+                }
+                output => serialized(output, &aliases)?,
+            }
+        }
+    };
+    Ok(output)
+}
+
+async fn load(cli: &Cli) -> Result<NetworksRegistry, Error> {
+    if let Some(path) = &cli.file {
+        return NetworksRegistry::from_file(path);
+    }
+    if let Some(url) = &cli.url {
+        return RegistryClient::new().from_url(url).await;
+    }
+    let version = match cli.registry_version {
+        None | Some(VersionArg::Latest) => RegistryVersion::Latest,
+        Some(VersionArg::Number(version)) => RegistryVersion::Number(version),
+    };
+    RegistryClient::new().from_version(version).await
+}
+
+/// Parses one of `values`, rejecting anything else so that typos don't silently match nothing
+fn known_values<T: for<'a> From<&'a str> + Clone + Send + Sync + 'static>(
+    values: &'static [&'static str],
+) -> impl TypedValueParser<Value = T> {
+    PossibleValuesParser::new(values).map(|value| T::from(value.as_str()))
+}
+
+fn parse_registry_version(value: &str) -> Result<VersionArg, Error> {
+    match value {
+        "latest" => Ok(VersionArg::Latest),
+        version => version.parse().map(VersionArg::Number),
+    }
+}
+
+/// Parses a service by its key in the registry, ignoring case, `-` and `_`, e.g. `tokenApi` or `token-api`
+fn parse_service(value: &str) -> Result<Service, String> {
+    let normalize = |name: &str| name.replace(['-', '_'], "").to_ascii_lowercase();
+    Service::ALL
+        .into_iter()
+        .find(|service| normalize(service.as_str()) == normalize(value))
+        .ok_or_else(|| {
+            let names: Vec<&str> = Service::ALL.iter().map(Service::as_str).collect();
+            format!("unknown service \"{}\", expected one of {}", value, names.join(", "))
+        })
+}
+
+fn serialized<T: Serialize + ?Sized>(output: Output, value: &T) -> Result<String, Box<dyn std::error::Error>> {
+    match output {
+        Output::Json => Ok(serde_json::to_string_pretty(value)? + "\n"),
+        Output::Yaml => Ok(serde_yaml::to_string(value)?),
+        Output::Table => unreachable!("tables are rendered by each command"),
+    }
+}
+
+fn network_details(network: &Network) -> String {
+    let join = |values: Option<&Vec<String>>| values.map(|values| values.join(", ")).unwrap_or_default();
+    let mut rows = vec![
+        vec!["id".to_string(), network.id.clone()],
+        vec!["name".to_string(), network.full_name.clone()],
+        vec!["type".to_string(), network.network_type.to_string()],
+        vec!["caip2".to_string(), network.caip2_id.clone()],
+        vec!["aliases".to_string(), join(network.aliases.as_ref())],
+        vec!["issuance rewards".to_string(), network.issuance_rewards.to_string()],
+    ];
+    if let Some(protocol) = network.graph_node.as_ref().and_then(|graph_node| graph_node.protocol.as_ref()) {
+        rows.push(vec!["protocol".to_string(), protocol.to_string()]);
+    }
+    for service in Service::ALL {
+        let urls = network.services.urls(service);
+        if !urls.is_empty() {
+            rows.push(vec![service.to_string(), urls.join(", ")]);
+        }
+    }
+    table(&["FIELD", "VALUE"], rows)
+}
+
+/// Renders rows as left-aligned columns separated by two spaces
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let headers = headers.iter().map(|header| header.to_string()).collect();
+    let mut out = String::new();
+    for row in std::iter::once(headers).chain(rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        out.push_str(line.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        let cli = Cli::try_parse_from([
+            "graph-networks",
+            "list",
+            "--type",
+            "mainnet",
+            "--service",
+            "token-api",
+            "-o",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(cli.output, Output::Json);
+        match cli.command {
+            Command::List { network_type, service, .. } => {
+                assert_eq!(network_type, vec![NetworkType::Mainnet]);
+                assert_eq!(service, vec![Service::TokenApi]);
+            }
+            command => panic!("expected list, got {:?}", command),
+        }
+
+        assert!(Cli::try_parse_from([
+            "graph-networks",
+            "get",
+            "mainnet",
+            "--file",
+            "registry.json",
+            "--url",
+            "https://example.com"
+        ])
+        .is_err());
+        assert!(Cli::try_parse_from(["graph-networks", "list", "--service", "rpc"]).is_err());
+        assert!(Cli::try_parse_from(["graph-networks", "list", "--type", "mainet"]).is_err());
+        assert!(Cli::try_parse_from(["graph-networks", "list", "--protocol", "etherium"]).is_err());
+        let cli = Cli::try_parse_from(["graph-networks", "list", "--protocol", "near", "--protocol", "other"]).unwrap();
+        assert!(matches!(cli.command, Command::List { protocol, .. } if protocol == [Protocol::Near, Protocol::Other]));
+
+        let cli = Cli::try_parse_from(["graph-networks", "aliases", "--registry-version", "v0.6.1"]).unwrap();
+        assert_eq!(cli.registry_version, Some(VersionArg::Number(RegistryVersionNumber::new(0, 6, 1))));
+        assert!(Cli::try_parse_from(["graph-networks", "aliases", "--registry-version", "0.6"]).is_err());
+    }
+
+    #[test]
+    fn test_table() {
+        let rows = vec![
+            vec!["mainnet".to_string(), "eth".to_string()],
+            vec!["arbitrum-one".to_string(), String::new()],
+        ];
+        assert_eq!(
+            table(&["ID", "ALIASES"], rows),
+            "ID            ALIASES\nmainnet       eth\narbitrum-one\n"
+        );
+    }
+}
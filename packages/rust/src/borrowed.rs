@@ -0,0 +1,249 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::value::RawValue;
+
+use crate::error::Error;
+use crate::types::{Network, NetworksRegistry};
+
+/// A registry borrowed from a JSON string, for looking up a few networks without parsing them all
+///
+/// Strings point into the JSON they were parsed from, unless they contain escapes. Networks keep
+/// only the fields used for lookups and their JSON, parsed into a [`Network`] by
+/// [`NetworkRef::to_network`] when needed.
+///
+/// # Example
+///
+/// ```
+/// use graph_networks_registry::NetworksRegistryRef;
+///
+/// # fn example(json: &str) -> Result<(), graph_networks_registry::Error> {
+/// let registry = NetworksRegistryRef::from_json(json)?;
+/// if let Some(mainnet) = registry.get_network_by_graph_id("eth") {
+///     let rpc_urls = mainnet.to_network()?.rpc_urls;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct NetworksRegistryRef<'a> {
+    /// Reference to the schema file
+    pub schema: Cow<'a, str>,
+
+    pub description: Cow<'a, str>,
+
+    /// List of networks
+    pub networks: Vec<NetworkRef<'a>>,
+
+    pub title: Cow<'a, str>,
+
+    /// Date and time of the last update, as published
+    pub updated_at: Cow<'a, str>,
+
+    /// Version of the registry
+    pub version: Cow<'a, str>,
+
+    json: &'a str,
+}
+
+/// A network of a [`NetworksRegistryRef`], with the fields used for lookups
+#[derive(Debug, Clone)]
+pub struct NetworkRef<'a> {
+    /// Established name of the network in The Graph ecosystem, e.g. mainnet
+    pub id: Cow<'a, str>,
+
+    /// Aliases for the network id, e.g. ethereum, eth
+    pub aliases: Vec<Cow<'a, str>>,
+
+    /// CAIP-2 Chain ID, e.g. eip155:1
+    pub caip2_id: Cow<'a, str>,
+
+    /// Display name of the network, e.g. Ethereum Mainnet
+    pub full_name: Cow<'a, str>,
+
+    /// Whether the network is a mainnet/testnet/devnet, as published
+    pub network_type: Cow<'a, str>,
+
+    json: &'a str,
+}
+
+/// Fields of the registry as deserialized, before they are paired with their JSON
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegistryFields<'a> {
+    #[serde(rename = "$schema", borrow)]
+    schema: Cow<'a, str>,
+    #[serde(borrow)]
+    description: Cow<'a, str>,
+    #[serde(borrow)]
+    networks: Vec<&'a RawValue>,
+    #[serde(borrow)]
+    title: Cow<'a, str>,
+    #[serde(borrow)]
+    updated_at: Cow<'a, str>,
+    #[serde(borrow)]
+    version: Cow<'a, str>,
+}
+
+/// Fields of a network as deserialized, before they are paired with their JSON
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NetworkFields<'a> {
+    #[serde(borrow)]
+    id: Cow<'a, str>,
+    #[serde(borrow, default, deserialize_with = "strs")]
+    aliases: Vec<Cow<'a, str>>,
+    #[serde(borrow)]
+    caip2_id: Cow<'a, str>,
+    #[serde(borrow)]
+    full_name: Cow<'a, str>,
+    #[serde(borrow)]
+    network_type: Cow<'a, str>,
+}
+
+impl<'a> NetworksRegistryRef<'a> {
+    /// Borrows a registry from a JSON string
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - A JSON string containing the networks registry data
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is invalid or doesn't match the expected format
+    pub fn from_json(json: &'a str) -> Result<Self, Error> {
+        let fields: RegistryFields = serde_json::from_str(json)?;
+        let networks = fields.networks.into_iter().map(NetworkRef::from_raw).collect::<Result<_, _>>()?;
+        Ok(NetworksRegistryRef {
+            schema: fields.schema,
+            description: fields.description,
+            networks,
+            title: fields.title,
+            updated_at: fields.updated_at,
+            version: fields.version,
+            json,
+        })
+    }
+
+    /// Looks up a network by its graph id (either its id field or one of its aliases)
+    ///
+    /// # Returns
+    ///
+    /// Returns the network with a matching id, or else the first one with a matching alias, like
+    /// [`NetworksRegistry::get_network_by_graph_id`]. `None` otherwise
+    pub fn get_network_by_graph_id(&self, id: &str) -> Option<&NetworkRef<'a>> {
+        self.networks
+            .iter()
+            .find(|network| network.id == id)
+            .or_else(|| self.networks.iter().find(|network| network.aliases.iter().any(|alias| alias == id)))
+    }
+
+    /// Looks up a network by its CAIP-2 chain ID, e.g. eip155:1
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(&NetworkRef)` if found, `None` otherwise
+    pub fn get_network_by_caip2_id(&self, chain_id: &str) -> Option<&NetworkRef<'a>> {
+        self.networks.iter().find(|network| network.caip2_id == chain_id)
+    }
+
+    /// Parses the whole registry into an owned [`NetworksRegistry`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a network doesn't match the expected format
+    pub fn to_registry(&self) -> Result<NetworksRegistry, Error> {
+        NetworksRegistry::from_json(self.json)
+    }
+}
+
+impl<'a> NetworkRef<'a> {
+    fn from_raw(raw: &'a RawValue) -> Result<Self, Error> {
+        let fields: NetworkFields = serde_json::from_str(raw.get())?;
+        Ok(NetworkRef {
+            id: fields.id,
+            aliases: fields.aliases,
+            caip2_id: fields.caip2_id,
+            full_name: fields.full_name,
+            network_type: fields.network_type,
+            json: raw.get(),
+        })
+    }
+
+    /// Returns the JSON of the network as it appears in the registry
+    pub fn json(&self) -> &'a str {
+        self.json
+    }
+
+    /// Parses all fields of the network
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the network doesn't match the expected format
+    pub fn to_network(&self) -> Result<Network, Error> {
+        Ok(serde_json::from_str(self.json)?)
+    }
+}
+
+fn strs<'de: 'a, 'a, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Cow<'a, str>>, D::Error> {
+    // a `Cow` inside a `Vec` is always owned, unless wrapped in a struct that borrows it
+    #[derive(Deserialize)]
+    struct Str<'a>(#[serde(borrow)] Cow<'a, str>);
+
+    let strs = Vec::<Str>::deserialize(deserializer)?;
+    Ok(strs.into_iter().map(|s| s.0).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::REGISTRY_JSON;
+
+    #[test]
+    fn test_borrowed_registry() {
+        let registry = NetworksRegistryRef::from_json(REGISTRY_JSON).unwrap();
+        assert_eq!(registry.version, "x.x.x");
+
+        let mainnet = registry.get_network_by_graph_id("eth").unwrap();
+        assert_eq!(mainnet.id, "mainnet");
+        assert!(matches!(mainnet.id, Cow::Borrowed(_)));
+        assert!(matches!(mainnet.aliases[..], [Cow::Borrowed("ethereum"), Cow::Borrowed("eth")]));
+        assert_eq!(registry.get_network_by_caip2_id("eip155:1").unwrap().full_name, "Ethereum Mainnet");
+        assert!(registry.get_network_by_graph_id("sepolia").is_none());
+
+        let network = mainnet.to_network().unwrap();
+        assert_eq!(network.short_name, "Ethereum");
+        assert!(network.issuance_rewards);
+        assert!(mainnet.json().trim_start().starts_with('{'));
+        assert_eq!(
+            registry.to_registry().unwrap().to_json().unwrap(),
+            NetworksRegistry::from_json(REGISTRY_JSON).unwrap().to_json().unwrap()
+        );
+
+        // escaped strings are unescaped into owned ones
+        let json = REGISTRY_JSON.replace(r#""eth""#, r#""\u0065th""#);
+        let registry = NetworksRegistryRef::from_json(&json).unwrap();
+        assert!(matches!(&registry.networks[0].aliases[1], Cow::Owned(alias) if alias == "eth"));
+        assert_eq!(registry.get_network_by_graph_id("eth").unwrap().id, "mainnet");
+
+        assert!(matches!(
+            NetworksRegistryRef::from_json(&REGISTRY_JSON.replace(r#""caip2Id": "eip155:1","#, "")),
+            Err(Error::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_borrowed_sample() {
+        let json = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../sample/TheGraphNetworksRegistry.json")).unwrap();
+        let registry = NetworksRegistryRef::from_json(&json).unwrap();
+        let owned = NetworksRegistry::from_json(&json).unwrap();
+        assert_eq!(registry.networks.len(), owned.networks.len());
+
+        let arbitrum = registry.get_network_by_graph_id("arbitrum-one").unwrap();
+        assert_eq!(arbitrum.caip2_id, owned.get_network_by_graph_id("arbitrum-one").unwrap().caip2_id);
+        assert_eq!(
+            serde_json::to_value(arbitrum.to_network().unwrap()).unwrap(),
+            serde_json::to_value(owned.get_network_by_graph_id("arbitrum-one").unwrap()).unwrap()
+        );
+    }
+}
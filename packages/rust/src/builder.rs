@@ -0,0 +1,681 @@
+use serde_json::Map;
+
+use crate::error::Error;
+use crate::types::*;
+use crate::version::SchemaVersion;
+
+/// Builder for [`Network`], created by [`Network::builder`]
+///
+/// `id`, `caip2_id`, `full_name` and `network_type` are required. `short_name` defaults to
+/// `full_name`, `issuance_rewards` to `false` and `services` to none. Methods for list fields
+/// add one item and can be repeated.
+///
+/// # Example
+///
+/// ```
+/// use graph_networks_registry::{Network, NetworkType, Services};
+///
+/// # fn example() -> Result<(), graph_networks_registry::Error> {
+/// let devnet = Network::builder()
+///     .id("devnet")
+///     .caip2_id("eip155:1337")
+///     .full_name("Local Devnet")
+///     .network_type(NetworkType::Devnet)
+///     .rpc_url("http://localhost:8545")
+///     .services(Services::builder().firehose("localhost:10015").build())
+///     .validate(true)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NetworkBuilder {
+    id: Option<String>,
+    caip2_id: Option<String>,
+    full_name: Option<String>,
+    network_type: Option<NetworkType>,
+    short_name: Option<String>,
+    second_name: Option<String>,
+    aliases: Vec<String>,
+    api_urls: Vec<ApiUrl>,
+    docs_url: Option<String>,
+    explorer_urls: Vec<String>,
+    firehose: Option<Firehose>,
+    graph_node: Option<GraphNode>,
+    icon: Option<Icon>,
+    indexer_docs_urls: Vec<IndexerDocsUrl>,
+    issuance_rewards: bool,
+    native_token: Option<String>,
+    relations: Vec<Relation>,
+    rpc_urls: Vec<String>,
+    services: Option<Services>,
+    token_api: Option<TokenApi>,
+    validate: bool,
+}
+
+/// Builder for [`Services`], created by [`Services::builder`]
+///
+/// Every service is optional. Each method adds one URL and can be repeated.
+#[derive(Debug, Clone, Default)]
+pub struct ServicesBuilder {
+    firehose: Vec<String>,
+    sps: Vec<String>,
+    subgraphs: Vec<String>,
+    substreams: Vec<String>,
+    token_api: Vec<String>,
+}
+
+/// Builder for [`Firehose`], created by [`Firehose::builder`]
+///
+/// `block_type`, `buf_url` and `bytes_encoding` are required.
+#[derive(Debug, Clone, Default)]
+pub struct FirehoseBuilder {
+    block_type: Option<String>,
+    buf_url: Option<String>,
+    bytes_encoding: Option<BytesEncoding>,
+    block_features: Vec<String>,
+    deprecated_at: Option<String>,
+    evm_extended_model: Option<bool>,
+    first_streamable_block: Option<FirstStreamableBlock>,
+}
+
+/// Builder for [`TokenApi`], created by [`TokenApi::builder`]
+///
+/// Every field is optional.
+#[derive(Debug, Clone, Default)]
+pub struct TokenApiBuilder {
+    deprecated_at: Option<String>,
+    features: Vec<Feature>,
+    network_id: Option<String>,
+}
+
+/// Builder for [`NetworksRegistry`], created by [`NetworksRegistry::builder`]
+///
+/// `updated_at` is required, so building the same input always gives the same registry. The other
+/// fields default to the schema URL and version this crate supports, the title and description of
+/// the published registry and no networks.
+#[derive(Debug, Clone, Default)]
+pub struct NetworksRegistryBuilder {
+    schema: Option<String>,
+    version: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    updated_at: Option<String>,
+    networks: Vec<Network>,
+    validate: bool,
+}
+
+impl Network {
+    /// Starts building a network
+    pub fn builder() -> NetworkBuilder {
+        NetworkBuilder::default()
+    }
+}
+
+impl Services {
+    /// Starts building the services of a network
+    pub fn builder() -> ServicesBuilder {
+        ServicesBuilder::default()
+    }
+}
+
+impl Firehose {
+    /// Starts building the Firehose information of a network
+    pub fn builder() -> FirehoseBuilder {
+        FirehoseBuilder::default()
+    }
+}
+
+impl TokenApi {
+    /// Starts building the Token API information of a network
+    pub fn builder() -> TokenApiBuilder {
+        TokenApiBuilder::default()
+    }
+}
+
+impl NetworksRegistry {
+    /// Starts building a registry
+    pub fn builder() -> NetworksRegistryBuilder {
+        NetworksRegistryBuilder::default()
+    }
+}
+
+impl NetworkBuilder {
+    /// Sets the network id, e.g. mainnet
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the CAIP-2 chain id, e.g. eip155:1
+    pub fn caip2_id(mut self, caip2_id: impl Into<String>) -> Self {
+        self.caip2_id = Some(caip2_id.into());
+        self
+    }
+
+    /// Sets the display name, e.g. Ethereum Mainnet
+    pub fn full_name(mut self, full_name: impl Into<String>) -> Self {
+        self.full_name = Some(full_name.into());
+        self
+    }
+
+    /// Sets whether the network is a mainnet, testnet, devnet or beacon chain
+    pub fn network_type(mut self, network_type: NetworkType) -> Self {
+        self.network_type = Some(network_type);
+        self
+    }
+
+    /// Sets the short display name, e.g. Ethereum (default: the full name)
+    pub fn short_name(mut self, short_name: impl Into<String>) -> Self {
+        self.short_name = Some(short_name.into());
+        self
+    }
+
+    /// Sets the second display name, e.g. Sepolia
+    pub fn second_name(mut self, second_name: impl Into<String>) -> Self {
+        self.second_name = Some(second_name.into());
+        self
+    }
+
+    /// Adds an alias of the network id
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    /// Adds an explorer API URL
+    pub fn api_url(mut self, kind: ApiUrlKind, url: impl Into<String>) -> Self {
+        self.api_urls.push(ApiUrl {
+            kind,
+            url: url.into(),
+            extra: Map::new(),
+        });
+        self
+    }
+
+    /// Sets the URL of the chain documentation
+    pub fn docs_url(mut self, docs_url: impl Into<String>) -> Self {
+        self.docs_url = Some(docs_url.into());
+        self
+    }
+
+    /// Adds a block explorer URL
+    pub fn explorer_url(mut self, url: impl Into<String>) -> Self {
+        self.explorer_urls.push(url.into());
+        self
+    }
+
+    /// Sets the Firehose block information
+    pub fn firehose(mut self, firehose: Firehose) -> Self {
+        self.firehose = Some(firehose);
+        self
+    }
+
+    /// Sets the Graph Node configuration
+    pub fn graph_node(mut self, graph_node: GraphNode) -> Self {
+        self.graph_node = Some(graph_node);
+        self
+    }
+
+    /// Sets the graph-node protocol, keeping the rest of the Graph Node configuration
+    pub fn protocol(mut self, protocol: Protocol) -> Self {
+        self.graph_node
+            .get_or_insert_with(|| GraphNode {
+                deprecated_at: None,
+                protocol: None,
+                extra: Map::new(),
+            })
+            .protocol = Some(protocol);
+        self
+    }
+
+    /// Sets the Web3Icons icon name, with all variants available
+    pub fn web3_icon(mut self, name: impl Into<String>) -> Self {
+        self.icon = Some(Icon {
+            web3_icons: Some(Web3Icons {
+                name: name.into(),
+                variants: None,
+                extra: Map::new(),
+            }),
+            extra: Map::new(),
+        });
+        self
+    }
+
+    /// Adds a link to documentation for running indexer components
+    pub fn indexer_docs_url(mut self, url: impl Into<String>, description: Option<String>) -> Self {
+        self.indexer_docs_urls.push(IndexerDocsUrl {
+            description,
+            url: url.into(),
+            extra: Map::new(),
+        });
+        self
+    }
+
+    /// Sets whether the network has issuance rewards on The Graph Network (default: false)
+    pub fn issuance_rewards(mut self, issuance_rewards: bool) -> Self {
+        self.issuance_rewards = issuance_rewards;
+        self
+    }
+
+    /// Sets the symbol of the native token, e.g. ETH
+    pub fn native_token(mut self, native_token: impl Into<String>) -> Self {
+        self.native_token = Some(native_token.into());
+        self
+    }
+
+    /// Adds a relation to another network
+    pub fn relation(mut self, kind: RelationKind, network: impl Into<String>) -> Self {
+        self.relations.push(Relation {
+            kind,
+            network: network.into(),
+            extra: Map::new(),
+        });
+        self
+    }
+
+    /// Adds an RPC URL
+    pub fn rpc_url(mut self, url: impl Into<String>) -> Self {
+        self.rpc_urls.push(url.into());
+        self
+    }
+
+    /// Sets the services available for the network (default: none)
+    pub fn services(mut self, services: Services) -> Self {
+        self.services = Some(services);
+        self
+    }
+
+    /// Sets the Token API information
+    pub fn token_api(mut self, token_api: TokenApi) -> Self {
+        self.token_api = Some(token_api);
+        self
+    }
+
+    /// Checks the network with [`Network::validate`] when building (default: false)
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Builds the network
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MissingField` if a required field is not set, or `Error::Invalid` if validation
+    /// is enabled and finds problems
+    pub fn build(self) -> Result<Network, Error> {
+        let full_name = required("Network", "full_name", self.full_name)?;
+        let network = Network {
+            aliases: non_empty(self.aliases),
+            api_urls: non_empty(self.api_urls),
+            caip2_id: required("Network", "caip2_id", self.caip2_id)?,
+            docs_url: self.docs_url,
+            explorer_urls: non_empty(self.explorer_urls),
+            firehose: self.firehose,
+            short_name: self.short_name.unwrap_or_else(|| full_name.clone()),
+            full_name,
+            graph_node: self.graph_node,
+            icon: self.icon,
+            id: required("Network", "id", self.id)?,
+            indexer_docs_urls: non_empty(self.indexer_docs_urls),
+            issuance_rewards: self.issuance_rewards,
+            native_token: self.native_token,
+            network_type: required("Network", "network_type", self.network_type)?,
+            relations: non_empty(self.relations),
+            rpc_urls: non_empty(self.rpc_urls),
+            second_name: self.second_name,
+            services: self.services.unwrap_or_else(|| Services::builder().build()),
+            token_api: self.token_api,
+            extra: Map::new(),
+        };
+        if self.validate {
+            check("Network", network.validate())?;
+        }
+        Ok(network)
+    }
+}
+
+impl ServicesBuilder {
+    /// Adds a Firehose gRPC URL
+    pub fn firehose(mut self, url: impl Into<String>) -> Self {
+        self.firehose.push(url.into());
+        self
+    }
+
+    /// Adds a Substreams-based subgraphs deployment URL
+    pub fn sps(mut self, url: impl Into<String>) -> Self {
+        self.sps.push(url.into());
+        self
+    }
+
+    /// Adds a subgraph deployment URL
+    pub fn subgraphs(mut self, url: impl Into<String>) -> Self {
+        self.subgraphs.push(url.into());
+        self
+    }
+
+    /// Adds a Substreams gRPC URL
+    pub fn substreams(mut self, url: impl Into<String>) -> Self {
+        self.substreams.push(url.into());
+        self
+    }
+
+    /// Adds a Token API URL
+    pub fn token_api(mut self, url: impl Into<String>) -> Self {
+        self.token_api.push(url.into());
+        self
+    }
+
+    /// Builds the services, leaving out the ones without URLs
+    pub fn build(self) -> Services {
+        Services {
+            firehose: non_empty(self.firehose),
+            sps: non_empty(self.sps),
+            subgraphs: non_empty(self.subgraphs),
+            substreams: non_empty(self.substreams),
+            token_api: non_empty(self.token_api),
+            extra: Map::new(),
+        }
+    }
+}
+
+impl FirehoseBuilder {
+    /// Sets the block type, e.g. sf.ethereum.type.v2.Block
+    pub fn block_type(mut self, block_type: impl Into<String>) -> Self {
+        self.block_type = Some(block_type.into());
+        self
+    }
+
+    /// Sets the protobuf definitions URL, e.g. https://buf.build/streamingfast/firehose-ethereum
+    pub fn buf_url(mut self, buf_url: impl Into<String>) -> Self {
+        self.buf_url = Some(buf_url.into());
+        self
+    }
+
+    /// Sets the bytes encoding
+    pub fn bytes_encoding(mut self, bytes_encoding: BytesEncoding) -> Self {
+        self.bytes_encoding = Some(bytes_encoding);
+        self
+    }
+
+    /// Adds a supported block feature
+    pub fn block_feature(mut self, feature: impl Into<String>) -> Self {
+        self.block_features.push(feature.into());
+        self
+    }
+
+    /// Sets when the network was deprecated in Firehose software, as an RFC 3339 date-time
+    pub fn deprecated_at(mut self, deprecated_at: impl Into<String>) -> Self {
+        self.deprecated_at = Some(deprecated_at.into());
+        self
+    }
+
+    /// Sets whether the extended EVM block model is supported
+    pub fn evm_extended_model(mut self, evm_extended_model: bool) -> Self {
+        self.evm_extended_model = Some(evm_extended_model);
+        self
+    }
+
+    /// Sets the first streamable block
+    pub fn first_streamable_block(mut self, height: i64, id: impl Into<String>) -> Self {
+        self.first_streamable_block = Some(FirstStreamableBlock {
+            height,
+            id: id.into(),
+            extra: Map::new(),
+        });
+        self
+    }
+
+    /// Builds the Firehose information
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MissingField` if a required field is not set
+    pub fn build(self) -> Result<Firehose, Error> {
+        Ok(Firehose {
+            block_features: non_empty(self.block_features),
+            block_type: required("Firehose", "block_type", self.block_type)?,
+            buf_url: required("Firehose", "buf_url", self.buf_url)?,
+            bytes_encoding: required("Firehose", "bytes_encoding", self.bytes_encoding)?,
+            deprecated_at: self.deprecated_at,
+            evm_extended_model: self.evm_extended_model,
+            first_streamable_block: self.first_streamable_block,
+            extra: Map::new(),
+        })
+    }
+}
+
+impl TokenApiBuilder {
+    /// Sets when the network was deprecated in Token API software, as an RFC 3339 date-time
+    pub fn deprecated_at(mut self, deprecated_at: impl Into<String>) -> Self {
+        self.deprecated_at = Some(deprecated_at.into());
+        self
+    }
+
+    /// Adds a supported feature
+    pub fn feature(mut self, feature: Feature) -> Self {
+        self.features.push(feature);
+        self
+    }
+
+    /// Sets the network id used by the Token API, an id or alias of a network in the registry
+    pub fn network_id(mut self, network_id: impl Into<String>) -> Self {
+        self.network_id = Some(network_id.into());
+        self
+    }
+
+    /// Builds the Token API information
+    pub fn build(self) -> TokenApi {
+        TokenApi {
+            deprecated_at: self.deprecated_at,
+            features: non_empty(self.features),
+            network_id: self.network_id,
+            extra: Map::new(),
+        }
+    }
+}
+
+impl NetworksRegistryBuilder {
+    /// Sets the `$schema` URL (default: the schema this crate supports)
+    pub fn schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    /// Sets the registry version (default: the schema version this crate supports, e.g. 0.7.0)
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Sets the title
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the description
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the time of the last update as an RFC 3339 date-time
+    pub fn updated_at(mut self, updated_at: impl Into<String>) -> Self {
+        self.updated_at = Some(updated_at.into());
+        self
+    }
+
+    /// Adds a network
+    pub fn network(mut self, network: Network) -> Self {
+        self.networks.push(network);
+        self
+    }
+
+    /// Checks the registry with [`NetworksRegistry::validate`] when building (default: false)
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Builds the registry
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MissingField` if `updated_at` is not set, or `Error::Invalid` if validation is
+    /// enabled and finds problems
+    pub fn build(self) -> Result<NetworksRegistry, Error> {
+        let schema_version = SchemaVersion::supported();
+        let registry = NetworksRegistry {
+            schema: self.schema.unwrap_or_else(|| schema_version.schema_url()),
+            description: self.description.unwrap_or_else(|| {
+                "This registry was generated and validated at https://github.com/graphprotocol/networks-registry".to_string()
+            }),
+            networks: self.networks,
+            title: self.title.unwrap_or_else(|| "The Graph networks registry".to_string()),
+            updated_at: required("NetworksRegistry", "updated_at", self.updated_at)?,
+            version: self.version.unwrap_or_else(|| format!("{}.0", schema_version)),
+            extra: Map::new(),
+            index: Default::default(),
+            key_order: Default::default(),
+        };
+        if self.validate {
+            check("NetworksRegistry", registry.validate())?;
+        }
+        Ok(registry)
+    }
+}
+
+fn required<T>(type_name: &'static str, field: &'static str, value: Option<T>) -> Result<T, Error> {
+    value.ok_or(Error::MissingField { type_name, field })
+}
+
+fn non_empty<T>(values: Vec<T>) -> Option<Vec<T>> {
+    (!values.is_empty()).then_some(values)
+}
+
+fn check(type_name: &'static str, issues: Vec<crate::validation::ValidationIssue>) -> Result<(), Error> {
+    if issues.is_empty() {
+        return Ok(());
+    }
+    Err(Error::Invalid { type_name, issues })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::ValidationIssue;
+
+    fn mainnet() -> NetworkBuilder {
+        Network::builder()
+            .id("mainnet")
+            .caip2_id("eip155:1")
+            .full_name("Ethereum Mainnet")
+            .network_type(NetworkType::Mainnet)
+    }
+
+    #[test]
+    fn test_build_network() {
+        let network = mainnet()
+            .short_name("Ethereum")
+            .alias("eth")
+            .alias("ethereum")
+            .protocol(Protocol::Ethereum)
+            .issuance_rewards(true)
+            .rpc_url("https://eth.example/rpc/{CUSTOM_API_KEY}")
+            .relation(RelationKind::L2Of, "beacon")
+            .services(Services::builder().firehose("eth.firehose.example:443").build())
+            .firehose(
+                Firehose::builder()
+                    .block_type("sf.ethereum.type.v2.Block")
+                    .buf_url("https://buf.build/streamingfast/firehose-ethereum")
+                    .bytes_encoding(BytesEncoding::The0Xhex)
+                    .first_streamable_block(0, "0xd4e5")
+                    .build()
+                    .unwrap(),
+            )
+            .token_api(TokenApi::builder().feature(Feature::Tokens).build())
+            .validate(true)
+            .build()
+            .unwrap();
+        assert_eq!(network.aliases, Some(vec!["eth".to_string(), "ethereum".to_string()]));
+        assert_eq!(network.graph_node.unwrap().protocol, Some(Protocol::Ethereum));
+        assert_eq!(network.services.urls(crate::Service::Firehose), ["eth.firehose.example:443"]);
+        assert_eq!(network.firehose.unwrap().bytes_encoding, BytesEncoding::The0Xhex);
+        assert_eq!(network.explorer_urls, None);
+
+        let network = mainnet().build().unwrap();
+        assert_eq!(network.short_name, "Ethereum Mainnet");
+        assert!(!network.issuance_rewards);
+        assert_eq!(network.services.firehose, None);
+        assert_eq!(network.aliases, None);
+
+        assert!(matches!(
+            Network::builder().id("mainnet").full_name("Ethereum Mainnet").build(),
+            Err(Error::MissingField {
+                type_name: "Network",
+                field: "caip2_id"
+            })
+        ));
+        assert!(matches!(
+            Firehose::builder().block_type("sf.ethereum.type.v2.Block").build(),
+            Err(Error::MissingField {
+                type_name: "Firehose",
+                field: "buf_url"
+            })
+        ));
+
+        assert!(mainnet().caip2_id("eip155").build().is_ok());
+        assert!(matches!(
+            mainnet().caip2_id("eip155").validate(true).build(),
+            Err(Error::Invalid { type_name: "Network", issues }) if matches!(issues[..], [ValidationIssue::MalformedCaip2Id { .. }])
+        ));
+    }
+
+    #[test]
+    fn test_build_registry() {
+        let registry = NetworksRegistry::builder()
+            .updated_at("2025-01-01T00:00:00Z")
+            .network(mainnet().alias("eth").build().unwrap())
+            .validate(true)
+            .build()
+            .unwrap();
+        assert_eq!(registry.get_network_by_graph_id("eth").unwrap().id, "mainnet");
+        assert!(registry.compatibility().is_compatible());
+        assert_eq!(registry.version_number().unwrap(), crate::RegistryVersionNumber::supported());
+
+        // round-trips through JSON like a loaded registry
+        let json = serde_json::to_string(&registry).unwrap();
+        assert_eq!(NetworksRegistry::from_json(&json).unwrap().networks.len(), 1);
+
+        let duplicate = NetworksRegistry::builder()
+            .updated_at("2025-01-01T00:00:00Z")
+            .network(mainnet().build().unwrap())
+            .network(mainnet().caip2_id("eip155:10").build().unwrap())
+            .validate(true)
+            .build();
+        let Err(err) = duplicate else {
+            panic!("expected a duplicate id");
+        };
+        assert_eq!(err.to_string(), "Invalid NetworksRegistry: duplicate network id \"mainnet\"");
+
+        // the same input builds the same registry
+        let build = || {
+            NetworksRegistry::builder()
+                .updated_at("2025-01-01T00:00:00Z")
+                .build()
+                .unwrap()
+                .to_json()
+                .unwrap()
+        };
+        assert_eq!(build(), build());
+        assert!(matches!(
+            NetworksRegistry::builder().build(),
+            Err(Error::MissingField {
+                type_name: "NetworksRegistry",
+                field: "updated_at"
+            })
+        ));
+    }
+}
@@ -0,0 +1,117 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+
+/// On-disk cache of fetched registry documents, one entry per URL
+///
+/// Each entry is stored as two files: `{key}.json` with the registry document as served,
+/// and `{key}.meta.json` with the URL and the validators used to revalidate it.
+#[derive(Debug, Clone)]
+pub(crate) struct RegistryCache {
+    dir: PathBuf,
+}
+
+/// A cached registry document
+#[derive(Debug, Clone)]
+pub(crate) struct CacheEntry {
+    pub(crate) body: String,
+    pub(crate) validators: Validators,
+}
+
+/// Response headers used to make conditional requests
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheMeta {
+    url: String,
+    #[serde(flatten)]
+    validators: Validators,
+}
+
+impl Validators {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Validators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+
+    /// Returns the `If-None-Match` / `If-Modified-Since` headers to revalidate a cached copy
+    pub(crate) fn to_conditional_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(value) = self.etag.as_deref().and_then(|etag| HeaderValue::from_str(etag).ok()) {
+            headers.insert(IF_NONE_MATCH, value);
+        }
+        if let Some(value) = self.last_modified.as_deref().and_then(|date| HeaderValue::from_str(date).ok()) {
+            headers.insert(IF_MODIFIED_SINCE, value);
+        }
+        headers
+    }
+}
+
+impl RegistryCache {
+    pub(crate) fn new(dir: impl Into<PathBuf>) -> Self {
+        RegistryCache { dir: dir.into() }
+    }
+
+    /// Returns the cached document for `url`, if there is a readable entry for it
+    pub(crate) fn load(&self, url: &str) -> Option<CacheEntry> {
+        let (body_path, meta_path) = self.paths(url);
+        let meta: CacheMeta = serde_json::from_str(&fs::read_to_string(meta_path).ok()?).ok()?;
+        if meta.url != url {
+            return None;
+        }
+        let body = fs::read_to_string(body_path).ok()?;
+        Some(CacheEntry {
+            body,
+            validators: meta.validators,
+        })
+    }
+
+    /// Stores the document fetched from `url`, replacing any previous entry
+    pub(crate) fn store(&self, url: &str, body: &str, validators: Validators) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let (body_path, meta_path) = self.paths(url);
+        let meta = CacheMeta {
+            url: url.to_string(),
+            validators,
+        };
+        write_atomic(&body_path, body.as_bytes())?;
+        write_atomic(&meta_path, serde_json::to_string(&meta)?.as_bytes())
+    }
+
+    fn paths(&self, url: &str) -> (PathBuf, PathBuf) {
+        let key: String = url
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        (self.dir.join(format!("{}.json", key)), self.dir.join(format!("{}.meta.json", key)))
+    }
+}
+
+/// Writes to a temporary file first so readers never see a partially written entry
+fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, contents)?;
+    fs::rename(tmp_path, path)
+}
@@ -0,0 +1,359 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+use crate::types::{Network, NetworksRegistry};
+
+/// CAIP-2 chain id, e.g. `eip155:1` or `bip122:000000000019d6689c085ae165831e93`
+///
+/// See <https://github.com/ChainAgnostic/CAIPs/blob/main/CAIPs/caip-2.md>
+///
+/// # Example
+///
+/// ```
+/// use graph_networks_registry::Caip2Id;
+///
+/// let chain_id: Caip2Id = "eip155:1".parse().unwrap();
+/// assert_eq!(chain_id.namespace(), "eip155");
+/// assert_eq!(chain_id.reference(), "1");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Caip2Id {
+    namespace: String,
+    reference: String,
+}
+
+/// CAIP-10 account id, e.g. `eip155:1:0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb`
+///
+/// See <https://github.com/ChainAgnostic/CAIPs/blob/main/CAIPs/caip-10.md>
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccountId {
+    chain_id: Caip2Id,
+    address: String,
+}
+
+/// CAIP-19 asset id, e.g. `eip155:1/erc20:0x6b175474e89094c44da98b954eedeac495271d0f`
+/// or `eip155:1/erc721:0x06012c8cf97BEaD5deAe237070F9587f8E7A266d/771769`
+///
+/// See <https://github.com/ChainAgnostic/CAIPs/blob/main/CAIPs/caip-19.md>
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AssetId {
+    chain_id: Caip2Id,
+    asset_namespace: String,
+    asset_reference: String,
+    token_id: Option<String>,
+}
+
+impl Caip2Id {
+    /// Creates a chain id from its parts
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `namespace` is not 3-8 characters of `[-a-z0-9]`
+    /// or `reference` is not 1-32 characters of `[-_a-zA-Z0-9]`
+    pub fn new(namespace: &str, reference: &str) -> Result<Self, Error> {
+        check_caip2(namespace, reference)?;
+        Ok(Caip2Id {
+            namespace: namespace.to_string(),
+            reference: reference.to_string(),
+        })
+    }
+
+    /// Returns the namespace, e.g. `eip155`
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Returns the reference within the namespace, e.g. `1`
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    /// Looks up the network with this chain id in `registry`
+    pub fn network<'a>(&self, registry: &'a NetworksRegistry) -> Option<&'a Network> {
+        registry.get_network_by_caip2(self)
+    }
+}
+
+impl AccountId {
+    /// Creates an account id from a chain id and an account address
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `address` is not 1-128 characters of `[-.%a-zA-Z0-9]`
+    pub fn new(chain_id: Caip2Id, address: &str) -> Result<Self, Error> {
+        check_part(address, 1, 128, is_address_char)
+            .map_err(|reason| invalid(format!("{}:{}", chain_id, address), "account address", reason))?;
+        Ok(AccountId {
+            chain_id,
+            address: address.to_string(),
+        })
+    }
+
+    /// Returns the chain the account is on
+    pub fn chain_id(&self) -> &Caip2Id {
+        &self.chain_id
+    }
+
+    /// Returns the account address, e.g. `0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb`
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Looks up the network the account is on in `registry`
+    pub fn network<'a>(&self, registry: &'a NetworksRegistry) -> Option<&'a Network> {
+        self.chain_id.network(registry)
+    }
+}
+
+impl AssetId {
+    /// Creates an asset id from a chain id, an asset namespace and reference, and an optional token id
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `asset_namespace` is not 3-8 characters of `[-a-z0-9]`,
+    /// `asset_reference` is not 1-128 characters of `[-.%a-zA-Z0-9]`
+    /// or `token_id` is not 1-78 characters of `[-.%a-zA-Z0-9]`
+    pub fn new(chain_id: Caip2Id, asset_namespace: &str, asset_reference: &str, token_id: Option<&str>) -> Result<Self, Error> {
+        let value = || {
+            let asset_id = format!("{}/{}:{}", chain_id, asset_namespace, asset_reference);
+            match token_id {
+                Some(token_id) => format!("{}/{}", asset_id, token_id),
+                None => asset_id,
+            }
+        };
+        check_part(asset_namespace, 3, 8, is_namespace_char).map_err(|reason| invalid(value(), "asset namespace", reason))?;
+        check_part(asset_reference, 1, 128, is_address_char).map_err(|reason| invalid(value(), "asset reference", reason))?;
+        if let Some(token_id) = token_id {
+            check_part(token_id, 1, 78, is_address_char).map_err(|reason| invalid(value(), "token id", reason))?;
+        }
+        Ok(AssetId {
+            chain_id,
+            asset_namespace: asset_namespace.to_string(),
+            asset_reference: asset_reference.to_string(),
+            token_id: token_id.map(str::to_string),
+        })
+    }
+
+    /// Returns the chain the asset is on
+    pub fn chain_id(&self) -> &Caip2Id {
+        &self.chain_id
+    }
+
+    /// Returns the asset namespace, e.g. `erc20` or `slip44`
+    pub fn asset_namespace(&self) -> &str {
+        &self.asset_namespace
+    }
+
+    /// Returns the asset reference within the namespace, e.g. a token contract address
+    pub fn asset_reference(&self) -> &str {
+        &self.asset_reference
+    }
+
+    /// Returns the token id for non-fungible assets
+    pub fn token_id(&self) -> Option<&str> {
+        self.token_id.as_deref()
+    }
+
+    /// Looks up the network the asset is on in `registry`
+    pub fn network<'a>(&self, registry: &'a NetworksRegistry) -> Option<&'a Network> {
+        self.chain_id.network(registry)
+    }
+}
+
+impl Network {
+    /// Returns the parsed CAIP-2 chain id of the network
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `caip2_id` is not a valid CAIP-2 chain id
+    pub fn caip2(&self) -> Result<Caip2Id, Error> {
+        self.caip2_id.parse()
+    }
+}
+
+impl FromStr for Caip2Id {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (namespace, reference) = split_caip2(s)?;
+        Ok(Caip2Id {
+            namespace: namespace.to_string(),
+            reference: reference.to_string(),
+        })
+    }
+}
+
+impl FromStr for AccountId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (chain_id, address) = s
+            .rsplit_once(':')
+            .ok_or_else(|| invalid(s.to_string(), "account id", "expected [namespace]:[reference]:[address]"))?;
+        AccountId::new(chain_id.parse()?, address)
+    }
+}
+
+impl FromStr for AssetId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expected = "expected [namespace]:[reference]/[asset namespace]:[asset reference][/token id]";
+        let mut parts = s.splitn(3, '/');
+        let (Some(chain_id), Some(asset)) = (parts.next(), parts.next()) else {
+            return Err(invalid(s.to_string(), "asset id", expected));
+        };
+        let (asset_namespace, asset_reference) = asset.split_once(':').ok_or_else(|| invalid(s.to_string(), "asset id", expected))?;
+        AssetId::new(chain_id.parse()?, asset_namespace, asset_reference, parts.next())
+    }
+}
+
+impl fmt::Display for Caip2Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.reference)
+    }
+}
+
+impl fmt::Display for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.chain_id, self.address)
+    }
+}
+
+impl fmt::Display for AssetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}:{}", self.chain_id, self.asset_namespace, self.asset_reference)?;
+        if let Some(token_id) = &self.token_id {
+            write!(f, "/{}", token_id)?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_serde_as_string {
+    ($($type:ty),*) => {
+        $(
+            impl Serialize for $type {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(self)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $type {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let s = String::deserialize(deserializer)?;
+                    s.parse().map_err(serde::de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+impl_serde_as_string!(Caip2Id, AccountId, AssetId);
+
+/// Splits a CAIP-2 chain id into its namespace and reference, checking them without allocating
+pub(crate) fn split_caip2(s: &str) -> Result<(&str, &str), Error> {
+    let (namespace, reference) = s
+        .split_once(':')
+        .ok_or_else(|| invalid(s.to_string(), "chain id", "expected [namespace]:[reference]"))?;
+    check_caip2(namespace, reference)?;
+    Ok((namespace, reference))
+}
+
+fn check_caip2(namespace: &str, reference: &str) -> Result<(), Error> {
+    let value = || format!("{}:{}", namespace, reference);
+    check_part(namespace, 3, 8, is_namespace_char).map_err(|reason| invalid(value(), "namespace", reason))?;
+    check_part(reference, 1, 32, |b| b == b'-' || b == b'_' || b.is_ascii_alphanumeric())
+        .map_err(|reason| invalid(value(), "reference", reason))
+}
+
+fn is_namespace_char(b: u8) -> bool {
+    b == b'-' || b.is_ascii_lowercase() || b.is_ascii_digit()
+}
+
+fn is_address_char(b: u8) -> bool {
+    b == b'-' || b == b'.' || b == b'%' || b.is_ascii_alphanumeric()
+}
+
+fn check_part(part: &str, min_len: usize, max_len: usize, is_valid_char: fn(u8) -> bool) -> Result<(), &'static str> {
+    if part.len() < min_len || part.len() > max_len {
+        return Err("invalid length");
+    }
+    if !part.bytes().all(is_valid_char) {
+        return Err("invalid character");
+    }
+    Ok(())
+}
+
+fn invalid(value: String, part: &'static str, reason: &'static str) -> Error {
+    Error::InvalidCaipId { value, part, reason }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::REGISTRY_JSON;
+
+    #[test]
+    fn test_caip2_id() {
+        let chain_id: Caip2Id = "bip122:000000000019d6689c085ae165831e93".parse().unwrap();
+        assert_eq!(chain_id.namespace(), "bip122");
+        assert_eq!(chain_id.reference(), "000000000019d6689c085ae165831e93");
+        assert_eq!(chain_id.to_string(), "bip122:000000000019d6689c085ae165831e93");
+
+        for invalid in [
+            "eip155",
+            "ei:1",
+            "eip155155:1",
+            "EIP155:1",
+            "eip155:",
+            "eip155:1:2",
+            "eip155:0x1.",
+            &format!("eip155:{}", "1".repeat(33)),
+        ] {
+            assert!(
+                matches!(invalid.parse::<Caip2Id>(), Err(Error::InvalidCaipId { .. })),
+                "{} should be invalid",
+                invalid
+            );
+        }
+
+        let json = serde_json::to_string(&chain_id).unwrap();
+        assert_eq!(json, "\"bip122:000000000019d6689c085ae165831e93\"");
+        assert_eq!(serde_json::from_str::<Caip2Id>(&json).unwrap(), chain_id);
+        assert!(serde_json::from_str::<Caip2Id>("\"eip155\"").is_err());
+    }
+
+    #[test]
+    fn test_account_and_asset_ids() {
+        let registry = NetworksRegistry::from_json(REGISTRY_JSON).expect("Failed to parse registry");
+
+        let account: AccountId = "eip155:1:0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb".parse().unwrap();
+        assert_eq!(account.chain_id().to_string(), "eip155:1");
+        assert_eq!(account.address(), "0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb");
+        assert_eq!(account.network(&registry).unwrap().id, "mainnet");
+        assert!("eip155:1".parse::<AccountId>().is_err());
+        assert!("eip155:1:0xab/16".parse::<AccountId>().is_err());
+
+        let asset: AssetId = "eip155:1/erc721:0x06012c8cf97BEaD5deAe237070F9587f8E7A266d/771769".parse().unwrap();
+        assert_eq!(asset.asset_namespace(), "erc721");
+        assert_eq!(asset.asset_reference(), "0x06012c8cf97BEaD5deAe237070F9587f8E7A266d");
+        assert_eq!(asset.token_id(), Some("771769"));
+        assert_eq!(asset.network(&registry).unwrap().id, "mainnet");
+        assert_eq!(
+            asset.to_string(),
+            "eip155:1/erc721:0x06012c8cf97BEaD5deAe237070F9587f8E7A266d/771769"
+        );
+
+        let asset: AssetId = "eip155:137/slip44:966".parse().unwrap();
+        assert_eq!(asset.token_id(), None);
+        assert!(asset.network(&registry).is_none());
+        assert!("eip155:1/erc20".parse::<AssetId>().is_err());
+        assert!("eip155:1/x:0x1".parse::<AssetId>().is_err());
+
+        assert_eq!(registry.networks[0].caip2().unwrap(), Caip2Id::new("eip155", "1").unwrap());
+    }
+}
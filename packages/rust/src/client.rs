@@ -1,4 +1,7 @@
+use crate::caip::{self, Caip2Id};
 use crate::error::Error;
+#[cfg(any(feature = "fetch", feature = "blocking"))]
+use crate::registry_client::RegistryClient;
 use crate::types::*;
 use crate::version::*;
 
@@ -6,7 +9,8 @@ impl std::str::FromStr for NetworksRegistry {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let registry = serde_json::from_str(s)?;
+        let registry: NetworksRegistry = serde_json::from_str(s).inspect_err(|_err| debug!(error = %_err, "failed to parse registry"))?;
+        registry.index.get(&registry.networks);
         Ok(registry)
     }
 }
@@ -41,41 +45,108 @@ impl NetworksRegistry {
 
     /// Creates a new NetworksRegistry by reading from a file
     ///
+    /// The file is parsed while it is read, and decompressed like in [`NetworksRegistry::from_reader`].
+    ///
     /// # Arguments
     ///
     /// * `path` - Path to the JSON file containing the networks registry data
     ///
     /// # Errors
     ///
-    /// Returns an error if the file cannot be read or contains invalid data
+    /// Returns an error if the file cannot be read or contains invalid data.
+    /// Parse errors are reported as `Error::ParseAt` with the path of the file
     pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
-        let contents = std::fs::read_to_string(path)?;
-        Self::from_json(&contents)
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)?;
+        Self::from_reader(file).map_err(|err| err.at(path.display().to_string()))
+    }
+
+    /// Creates a new NetworksRegistry from a JSON string, rejecting registries with a different schema version
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - A JSON string containing the networks registry data
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is invalid, or `Error::SchemaVersionMismatch` if its schema version
+    /// is not the one this crate supports
+    pub fn from_json_strict(json: &str) -> Result<Self, Error> {
+        let registry = Self::from_json(json)?;
+        registry.ensure_compatible()?;
+        Ok(registry)
+    }
+
+    /// Creates a new NetworksRegistry by reading from a file, rejecting registries with a different schema version
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the JSON file containing the networks registry data
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or contains invalid data, or `Error::SchemaVersionMismatch`
+    /// if its schema version is not the one this crate supports
+    pub fn from_file_strict<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+        let registry = Self::from_file(path)?;
+        registry.ensure_compatible()?;
+        Ok(registry)
     }
 
     /// Fetches and creates a NetworksRegistry from the latest compatible version available online
-    /// Library version 0.5.x will use the latest registry version 0.5.y even if 0.6.z is available
+    /// The crate supports one schema version, e.g. with schema 0.7 it uses the latest registry version
+    /// 0.7.y even if 0.8.z is available
     ///
     /// # Errors
     ///
     /// Returns an error if the network request fails or the response contains invalid data
     #[cfg(feature = "fetch")]
     pub async fn from_latest_version() -> Result<Self, Error> {
-        Self::from_version(RegistryVersion::Latest).await
+        RegistryClient::new().from_version(RegistryVersion::Latest).await
     }
 
     /// Fetches and creates a NetworksRegistry from a specific version available online
     ///
     /// # Arguments
     ///
-    /// * `version` - The version string to fetch (e.g., "v0.5.0")
+    /// * `version` - The version string to fetch (e.g., "v0.5.0" or "0.5.0")
     ///
     /// # Errors
     ///
-    /// Returns an error if the network request fails or the response contains invalid data
+    /// Returns `Error::InvalidVersion` if `version` is not a valid version number, or an error if the
+    /// network request fails or the response contains invalid data
     #[cfg(feature = "fetch")]
     pub async fn from_exact_version(version: &str) -> Result<Self, Error> {
-        Self::from_version(RegistryVersion::Exact(version)).await
+        RegistryClient::new().from_version(RegistryVersion::Exact(version)).await
+    }
+
+    /// Fetches and creates a NetworksRegistry from the latest compatible version available online,
+    /// blocking the current thread until the request completes
+    ///
+    /// Does not require an async runtime, but must not be called from within one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the network request fails or the response contains invalid data
+    #[cfg(feature = "blocking")]
+    pub fn from_latest_version_blocking() -> Result<Self, Error> {
+        RegistryClient::new().from_version_blocking(RegistryVersion::Latest)
+    }
+
+    /// Fetches and creates a NetworksRegistry from a specific version available online,
+    /// blocking the current thread until the request completes
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version string to fetch (e.g., "v0.5.0" or "0.5.0")
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidVersion` if `version` is not a valid version number, or an error if the
+    /// network request fails or the response contains invalid data
+    #[cfg(feature = "blocking")]
+    pub fn from_exact_version_blocking(version: &str) -> Result<Self, Error> {
+        RegistryClient::new().from_version_blocking(RegistryVersion::Exact(version))
     }
 
     /// Looks up a network by its ID
@@ -93,7 +164,8 @@ impl NetworksRegistry {
     /// This function is deprecated. Use `get_network_by_graph_id` instead.
     #[deprecated(since = "0.7.0", note = "Use get_network_by_graph_id instead")]
     pub fn get_network_by_id<'a>(&'a self, id: &str) -> Option<&'a Network> {
-        self.networks.iter().find(|network| network.id == id)
+        let index = self.index.get(&self.networks);
+        index.resolve(&self.networks, index.id(id), |network| network.id == id)
     }
 
     /// Looks up a network by one of its aliases
@@ -111,12 +183,8 @@ impl NetworksRegistry {
     /// This function is deprecated. Use `get_network_by_graph_id` instead.
     #[deprecated(since = "0.7.0", note = "Use get_network_by_graph_id instead")]
     pub fn get_network_by_alias<'a>(&'a self, alias: &str) -> Option<&'a Network> {
-        self.networks.iter().find(|network| {
-            network
-                .aliases
-                .as_ref()
-                .map_or(false, |aliases| aliases.contains(&alias.to_string()))
-        })
+        let index = self.index.get(&self.networks);
+        index.resolve(&self.networks, index.alias(alias), |network| has_alias(network, alias))
     }
 
     /// Looks up a network by its graph id (either its id field or one of its aliases)
@@ -127,11 +195,14 @@ impl NetworksRegistry {
     ///
     /// # Returns
     ///
-    /// Returns `Some(&Network)` if found, `None` otherwise
+    /// Returns `Some(&Network)` if found, `None` otherwise. A network with `id` as its id is
+    /// preferred over one with `id` as an alias, like in [`NetworksRegistry::relation_graph`]
     pub fn get_network_by_graph_id<'a>(&'a self, id: &str) -> Option<&'a Network> {
-        self.networks
-            .iter()
-            .find(|network| network.id == id || network.aliases.as_ref().map_or(false, |aliases| aliases.contains(&id.to_string())))
+        // the same rule as `NetworksIndex::graph_id`, checking each step against a stale index
+        let index = self.index.get(&self.networks);
+        index
+            .resolve(&self.networks, index.id(id), |network| network.id == id)
+            .or_else(|| index.resolve(&self.networks, index.alias(id), |network| has_alias(network, id)))
     }
 
     /// Looks up a network by its CAIP-2 chain ID
@@ -142,45 +213,72 @@ impl NetworksRegistry {
     ///
     /// # Returns
     ///
-    /// Returns `Some(&Network)` if found, `None` otherwise
-    /// ```
+    /// Returns `Some(&Network)` if found, `None` otherwise, including when `chain_id` is malformed.
+    /// Use `try_get_network_by_caip2_id` to tell the two apart
     pub fn get_network_by_caip2_id<'a>(&'a self, chain_id: &str) -> Option<&'a Network> {
-        // Check if the chain_id is in the correct format (contains a colon)
-        if !chain_id.contains(':') {
-            eprintln!("Warning: CAIP-2 Chain ID should be in the format '[namespace]:[reference]', e.g., 'eip155:1'");
-            return None;
+        match self.try_get_network_by_caip2_id(chain_id) {
+            Ok(network) => network,
+            Err(_err) => {
+                warn!(chain_id, error = %_err, "CAIP-2 chain id should be in the format [namespace]:[reference], e.g. eip155:1");
+                None
+            }
         }
+    }
 
-        self.networks.iter().find(|&network| network.caip2_id == chain_id)
+    /// Looks up a network by its CAIP-2 chain ID, rejecting malformed chain IDs
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_id` - The CAIP-2 chain ID in the format "[namespace]:[reference]" (e.g., "eip155:1")
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Some(&Network))` if found, `Ok(None)` otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidCaipId` if `chain_id` is not a valid CAIP-2 chain ID
+    pub fn try_get_network_by_caip2_id<'a>(&'a self, chain_id: &str) -> Result<Option<&'a Network>, Error> {
+        let (namespace, reference) = caip::split_caip2(chain_id)?;
+        let index = self.index.get(&self.networks);
+        let pos = index.caip2_id(namespace, reference);
+        Ok(index.resolve(&self.networks, pos, |network| network.caip2_id == chain_id))
     }
 
-    #[cfg(feature = "fetch")]
-    async fn fetch_registry(url: &str) -> Result<Self, Error> {
-        let response = reqwest::get(url).await?;
-        if !response.status().is_success() {
-            return Err(Error::Http(response.error_for_status().unwrap_err()));
-        }
-        let text = response.text().await?;
-        Self::from_json(&text)
+    /// Looks up a network by a parsed CAIP-2 chain ID
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(&Network)` if found, `None` otherwise
+    pub fn get_network_by_caip2<'a>(&'a self, chain_id: &Caip2Id) -> Option<&'a Network> {
+        let (namespace, reference) = (chain_id.namespace(), chain_id.reference());
+        let index = self.index.get(&self.networks);
+        index.resolve(&self.networks, index.caip2_id(namespace, reference), |network| {
+            network.caip2_id.split_once(':') == Some((namespace, reference))
+        })
     }
 
-    #[cfg(feature = "fetch")]
-    async fn from_version(version: RegistryVersion<'_>) -> Result<Self, Error> {
-        match Self::fetch_registry(&version.get_primary_url()).await {
-            Ok(registry) => Ok(registry),
-            Err(primary_err) => {
-                let fallback_url = version.get_fallback_url();
-                Self::fetch_registry(&fallback_url).await.map_err(|_| primary_err)
-            }
-        }
+    /// Rebuilds the lookup index used by the `get_network_by_*` methods
+    ///
+    /// The index is built once when the registry is loaded, and ids, aliases and CAIP-2 ids that are
+    /// not in it are not found. Call this after editing the ids, aliases or CAIP-2 ids of entries
+    /// in `networks`. Lookups after adding or removing entries fall back to a linear scan until
+    /// the next call.
+    pub fn reindex(&mut self) {
+        self.index.reset();
+        self.index.get(&self.networks);
     }
 }
 
+fn has_alias(network: &Network, alias: &str) -> bool {
+    network.aliases.iter().flatten().any(|a| a == alias)
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
-    const REGISTRY_JSON: &str = r#"{
+    pub(crate) const REGISTRY_JSON: &str = r#"{
         "$schema": "https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_vx_x.json",
         "version": "x.x.x",
         "title": "Test Registry",
@@ -201,6 +299,7 @@ mod tests {
     }"#;
 
     #[test]
+    #[allow(deprecated)]
     fn test_get_network() {
         let registry = NetworksRegistry::from_json(REGISTRY_JSON).expect("Failed to parse registry");
 
@@ -233,175 +332,102 @@ mod tests {
         assert!(network.is_none());
     }
 
-    #[cfg(feature = "fetch")]
-    mod fetch_tests {
-        use super::*;
-        use crate::version::{set_base_urls, SCHEMA_VERSION};
-        use mockito::Server;
-
-        #[tokio::test]
-        async fn test_fallback_with_mock_server() {
-            let registry_path = format!("/TheGraphNetworksRegistry_v{}_x.json", SCHEMA_VERSION);
-
-            // Create two mock servers for primary and fallback URLs
-            let mut primary_server = Server::new_async().await;
-            let mut fallback_server = Server::new_async().await;
-
-            // Set up the mock servers
-            set_base_urls(&primary_server.url(), &fallback_server.url());
-
-            // Test Case 1: Primary succeeds
-            let primary_mock = primary_server
-                .mock("GET", registry_path.as_str())
-                .with_status(200)
-                .with_body(REGISTRY_JSON)
-                .create_async()
-                .await;
-
-            let fallback_mock = fallback_server
-                .mock("GET", registry_path.as_str())
-                .with_status(200)
-                .expect(0)
-                .with_body(REGISTRY_JSON)
-                .create_async()
-                .await;
-
-            let result = NetworksRegistry::from_latest_version().await;
-            assert!(result.is_ok(), "Should succeed with primary URL");
-            let registry = result.unwrap();
-            assert!(registry.get_network_by_graph_id("mainnet").is_some());
-            primary_mock.assert();
-            fallback_mock.assert();
-
-            // Test Case 2: Primary fails, fallback succeeds
-            let primary_mock = primary_server
-                .mock("GET", registry_path.as_str())
-                .with_status(500)
-                .create_async()
-                .await;
-
-            let fallback_mock = fallback_server
-                .mock("GET", registry_path.as_str())
-                .with_status(200)
-                .with_body(REGISTRY_JSON)
-                .create_async()
-                .await;
-
-            let result = NetworksRegistry::from_latest_version().await;
-            assert!(result.is_ok(), "Should succeed using fallback URL");
-            let registry = result.unwrap();
-            assert!(registry.get_network_by_graph_id("mainnet").is_some());
-            primary_mock.assert();
-            fallback_mock.assert();
-
-            // Test Case 3: Both primary and fallback fail
-            let primary_mock = primary_server
-                .mock("GET", registry_path.as_str())
-                .with_status(200)
-                .with_body("bye")
-                .create_async()
-                .await;
-
-            let fallback_mock = fallback_server
-                .mock("GET", registry_path.as_str())
-                .with_status(404)
-                .create_async()
-                .await;
-
-            let result = NetworksRegistry::from_latest_version().await;
-            assert!(result.is_err(), "Should fail when both URLs fail");
-            primary_mock.assert();
-            fallback_mock.assert();
-        }
+    #[test]
+    fn test_lookup_order_and_reindex() {
+        let mut registry = NetworksRegistry::from_json(REGISTRY_JSON).expect("Failed to parse registry");
+
+        // an id takes precedence over an alias, wherever the networks are in the list
+        let mut sepolia = registry.networks[0].clone();
+        sepolia.id = "sepolia".to_string();
+        sepolia.caip2_id = "eip155:11155111".to_string();
+        sepolia.aliases = Some(vec!["mainnet".to_string(), "sep".to_string()]);
+        registry.networks.push(sepolia);
+
+        // lookups before reindex still see the new network through the stale index fallback
+        assert_eq!(registry.get_network_by_graph_id("sep").unwrap().id, "sepolia");
+
+        registry.reindex();
+        assert_eq!(registry.get_network_by_graph_id("mainnet").unwrap().id, "mainnet");
+        assert_eq!(registry.get_network_by_graph_id("sep").unwrap().id, "sepolia");
+        assert_eq!(registry.get_network_by_caip2_id("eip155:11155111").unwrap().id, "sepolia");
+        assert!(registry.get_network_by_caip2_id("eip155:10").is_none());
+
+        // edits in place are never served from a stale position
+        registry.networks.swap(0, 1);
+        assert_eq!(registry.get_network_by_graph_id("mainnet").unwrap().id, "mainnet");
+        assert_eq!(registry.get_network_by_graph_id("sep").unwrap().id, "sepolia");
+        assert_eq!(registry.get_network_by_caip2_id("eip155:1").unwrap().id, "mainnet");
+        assert_eq!(registry.networks[0].id, "sepolia");
+
+        // networks renamed in place are found under their new id once reindexed
+        registry.networks[1].id = "ethereum-mainnet".to_string();
+        registry.networks[1].aliases = None;
+        assert!(registry.get_network_by_graph_id("eth").is_none());
+        assert!(registry.get_network_by_graph_id("ethereum-mainnet").is_none());
+        registry.reindex();
+        assert_eq!(registry.get_network_by_graph_id("ethereum-mainnet").unwrap().id, "ethereum-mainnet");
+    }
 
-        #[tokio::test]
-        async fn test_http_errors() {
-            let mut primary_server = Server::new_async().await;
-            let mut fallback_server = Server::new_async().await;
-            let registry_path = format!("/TheGraphNetworksRegistry_v{}_x.json", SCHEMA_VERSION);
-
-            // Setup mock servers
-            set_base_urls(&primary_server.url(), &fallback_server.url());
-
-            // Test Case 1: Invalid JSON response
-            let primary_mock = primary_server
-                .mock("GET", registry_path.as_str())
-                .with_status(200)
-                .with_body("{invalid_json")
-                .create_async()
-                .await;
-
-            let result = NetworksRegistry::from_latest_version().await;
-            assert!(matches!(result, Err(Error::Parse(_))));
-            primary_mock.assert();
-
-            // Test Case 2: HTTP 404 on both servers
-            let primary_mock = primary_server
-                .mock("GET", registry_path.as_str())
-                .with_status(404)
-                .create_async()
-                .await;
-
-            let fallback_mock = fallback_server
-                .mock("GET", registry_path.as_str())
-                .with_status(404)
-                .create_async()
-                .await;
-
-            let result = NetworksRegistry::from_latest_version().await;
-            assert!(matches!(result, Err(Error::Http(_))));
-            primary_mock.assert();
-            fallback_mock.assert();
-
-            // Test Case 3: Empty response
-            let primary_mock = primary_server
-                .mock("GET", registry_path.as_str())
-                .with_status(200)
-                .with_body("")
-                .create_async()
-                .await;
-
-            let result = NetworksRegistry::from_latest_version().await;
-            assert!(matches!(result, Err(Error::Parse(_))));
-            primary_mock.assert();
-
-            // Test Case 4: Valid JSON but invalid schema
-            let invalid_schema_json = r#"{
-                "$schema": "https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_vx_x.json",
-                "version": "x.x.x",
-                "title": "Test Registry",
-                "description": "Test Registry",
-                "updatedAt": "2025-01-01T00:00:00Z",
-                "networks": "not_an_array"
-            }"#;
-
-            let primary_mock = primary_server
-                .mock("GET", registry_path.as_str())
-                .with_status(200)
-                .with_body(invalid_schema_json)
-                .create_async()
-                .await;
-
-            let result = NetworksRegistry::from_latest_version().await;
-            assert!(matches!(result, Err(Error::Parse(_))));
-            primary_mock.assert();
-
-            // Test Case 5: Connection refused (HTTP IO error)
-            set_base_urls("http://localhost:1", "invalid_url");
-
-            let result = NetworksRegistry::from_latest_version().await;
-            assert!(matches!(result, Err(Error::Http(_))));
-        }
+    #[test]
+    fn test_caip2_lookups() {
+        let registry = NetworksRegistry::from_json(REGISTRY_JSON).expect("Failed to parse registry");
+
+        assert_eq!(registry.get_network_by_caip2_id("eip155:1").unwrap().id, "mainnet");
+        assert!(registry.get_network_by_caip2_id("eip155:2").is_none());
+        assert!(registry.get_network_by_caip2_id("mainnet").is_none());
+
+        assert_eq!(registry.try_get_network_by_caip2_id("eip155:1").unwrap().unwrap().id, "mainnet");
+        assert!(matches!(registry.try_get_network_by_caip2_id("eip155:2"), Ok(None)));
+        assert!(matches!(
+            registry.try_get_network_by_caip2_id("mainnet"),
+            Err(Error::InvalidCaipId { .. })
+        ));
+
+        let chain_id = "eip155:1".parse().unwrap();
+        assert_eq!(registry.get_network_by_caip2(&chain_id).unwrap().id, "mainnet");
+    }
 
-        #[test]
-        fn test_io_errors() {
-            // Test non-existent file
-            let result = NetworksRegistry::from_file("/non/existent/path.json");
-            assert!(matches!(result, Err(Error::Io(_))));
+    #[test]
+    fn test_strict_loading() {
+        // the test registry has no schema version
+        assert!(NetworksRegistry::from_json(REGISTRY_JSON).is_ok());
+        assert!(matches!(
+            NetworksRegistry::from_json_strict(REGISTRY_JSON),
+            Err(Error::SchemaVersionMismatch { .. })
+        ));
+
+        let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/../../sample/TheGraphNetworksRegistry.json");
+        let registry = NetworksRegistry::from_file_strict(sample).expect("Sample registry should match the crate schema version");
+        assert_eq!(registry.compatibility(), Compatibility::Compatible);
+    }
 
-            // Test directory instead of file
-            let result = NetworksRegistry::from_file("/tmp");
-            assert!(matches!(result, Err(Error::Io(_))));
+    #[test]
+    fn test_io_errors() {
+        // Test non-existent file
+        let result = NetworksRegistry::from_file("/non/existent/path.json");
+        assert!(matches!(result, Err(Error::Io(_))));
+
+        // Test directory instead of file
+        let result = NetworksRegistry::from_file("/tmp");
+        assert!(matches!(result, Err(Error::Io(_))));
+
+        // Test invalid contents, the error includes the path
+        let path = std::env::temp_dir().join(format!("graph-networks-registry-invalid-{}.json", std::process::id()));
+        std::fs::write(&path, "{invalid_json").unwrap();
+        let result = NetworksRegistry::from_file(&path);
+        assert!(matches!(&result, Err(Error::ParseAt { location, .. }) if *location == path.display().to_string()));
+
+        // type errors keep the line and column of the problem
+        let json = REGISTRY_JSON.replace(r#""issuanceRewards": true"#, r#""issuanceRewards": "yes""#);
+        let line = json.lines().position(|line| line.contains(r#""yes""#)).unwrap() + 1;
+        std::fs::write(&path, &json).unwrap();
+        match NetworksRegistry::from_file(&path) {
+            Err(Error::ParseAt { source, .. }) => {
+                assert_eq!(source.line(), line);
+                assert!(source.to_string().contains(&format!("at line {} column", line)));
+            }
+            result => panic!("expected a parse error, got {:?}", result),
         }
+        std::fs::remove_file(path).unwrap();
     }
 }
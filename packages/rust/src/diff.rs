@@ -0,0 +1,277 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::types::*;
+
+/// Changes between two registries, returned by [`NetworksRegistry::diff`]
+///
+/// Serializes to JSON with camelCase fields, and displays as a human-readable summary.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryDiff {
+    /// Changes to registry-level fields such as `version` and `updatedAt`
+    pub registry: Vec<FieldChange>,
+
+    /// Ids of networks only in the new registry
+    pub added: Vec<String>,
+
+    /// Ids of networks only in the old registry
+    pub removed: Vec<String>,
+
+    /// Field changes of networks in both registries, by network id
+    pub changed: BTreeMap<String, Vec<FieldChange>>,
+}
+
+/// A field whose value differs between two registries
+///
+/// Missing fields and `None`s are `null`. Lists are compared as a whole.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Path of the field using its JSON names, e.g. `issuanceRewards` or `graphNode.deprecatedAt`
+    pub field: String,
+
+    pub old: Value,
+
+    pub new: Value,
+}
+
+impl NetworksRegistry {
+    /// Compares the registry with a newer one
+    ///
+    /// Networks are matched by `id`. Every field is compared, including fields unknown to this
+    /// version of the crate.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use graph_networks_registry::NetworksRegistry;
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let old = NetworksRegistry::from_file("TheGraphNetworksRegistry_v0_6_0.json")?;
+    /// let new = NetworksRegistry::from_file("TheGraphNetworksRegistry_v0_7_0.json")?;
+    /// let diff = old.diff(&new);
+    /// println!("{}", diff);
+    /// println!("{}", serde_json::to_string_pretty(&diff)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff(&self, other: &NetworksRegistry) -> RegistryDiff {
+        let mut diff = RegistryDiff::default();
+        let mut old_registry = to_object(self);
+        let mut new_registry = to_object(other);
+        old_registry.remove("networks");
+        new_registry.remove("networks");
+        diff_objects("", &old_registry, &new_registry, &mut diff.registry);
+
+        let mut old_networks: HashMap<&str, &Network> = HashMap::with_capacity(self.networks.len());
+        for network in &self.networks {
+            old_networks.entry(&network.id).or_insert(network);
+        }
+        let mut new_networks: HashMap<&str, &Network> = HashMap::with_capacity(other.networks.len());
+        for network in &other.networks {
+            new_networks.entry(&network.id).or_insert(network);
+        }
+
+        for network in &self.networks {
+            if !new_networks.contains_key(network.id.as_str()) && !diff.removed.contains(&network.id) {
+                diff.removed.push(network.id.clone());
+            }
+        }
+        for network in &other.networks {
+            match old_networks.remove(network.id.as_str()) {
+                Some(old) => {
+                    let mut changes = Vec::new();
+                    diff_objects("", &to_object(old), &to_object(network), &mut changes);
+                    if !changes.is_empty() {
+                        diff.changed.insert(network.id.clone(), changes);
+                    }
+                }
+                None if new_networks
+                    .get(network.id.as_str())
+                    .is_some_and(|&first| std::ptr::eq(first, network)) =>
+                {
+                    diff.added.push(network.id.clone());
+                }
+                None => {}
+            }
+        }
+
+        diff
+    }
+}
+
+impl RegistryDiff {
+    /// Returns `true` if the registries are identical
+    pub fn is_empty(&self) -> bool {
+        self.registry.is_empty() && self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for RegistryDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no changes");
+        }
+        for change in &self.registry {
+            writeln!(f, "{}", change)?;
+        }
+        for id in &self.added {
+            writeln!(f, "+ {}", id)?;
+        }
+        for id in &self.removed {
+            writeln!(f, "- {}", id)?;
+        }
+        for (id, changes) in &self.changed {
+            writeln!(f, "~ {}", id)?;
+            for change in changes {
+                writeln!(f, "    {}", change)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.old, &self.new) {
+            // lists of values are shown as the items added and removed
+            (Value::Array(_) | Value::Null, Value::Array(_) | Value::Null) if !(self.old.is_null() && self.new.is_null()) => {
+                let old = self.old.as_array().map(Vec::as_slice).unwrap_or_default();
+                let new = self.new.as_array().map(Vec::as_slice).unwrap_or_default();
+                let added: Vec<String> = new.iter().filter(|v| !old.contains(v)).map(Value::to_string).collect();
+                let removed: Vec<String> = old.iter().filter(|v| !new.contains(v)).map(Value::to_string).collect();
+                write!(f, "{}:", self.field)?;
+                if !added.is_empty() {
+                    write!(f, " added {}", added.join(", "))?;
+                }
+                if !removed.is_empty() {
+                    write!(f, "{} removed {}", if added.is_empty() { "" } else { ";" }, removed.join(", "))?;
+                }
+                if added.is_empty() && removed.is_empty() {
+                    write!(f, " reordered")?;
+                }
+                Ok(())
+            }
+            (old, new) => write!(f, "{}: {} -> {}", self.field, old, new),
+        }
+    }
+}
+
+fn to_object<T: Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(object)) => object,
+        _ => Map::new(),
+    }
+}
+
+/// Collects the differences between two objects, recursing into nested objects
+fn diff_objects(path: &str, old: &Map<String, Value>, new: &Map<String, Value>, changes: &mut Vec<FieldChange>) {
+    let mut keys: Vec<&String> = old.keys().chain(new.keys().filter(|key| !old.contains_key(*key))).collect();
+    keys.sort();
+    for key in keys {
+        let field = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+        let old = old.get(key).unwrap_or(&Value::Null);
+        let new = new.get(key).unwrap_or(&Value::Null);
+        match (old, new) {
+            (Value::Object(old), Value::Object(new)) => diff_objects(&field, old, new, changes),
+            (old, new) if old != new => changes.push(FieldChange {
+                field,
+                old: old.clone(),
+                new: new.clone(),
+            }),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::REGISTRY_JSON;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff() {
+        let old = NetworksRegistry::from_json(&REGISTRY_JSON.replace(
+            r#""services": {}"#,
+            r#""services": { "firehose": ["eth.firehose.example:443"] }, "graphNode": { "protocol": "ethereum" }"#,
+        ))
+        .unwrap();
+        let new_json = REGISTRY_JSON
+            .replace(r#""version": "x.x.x""#, r#""version": "x.x.y""#)
+            .replace(r#"["ethereum", "eth"]"#, r#"["eth", "eth-mainnet"]"#)
+            .replace(r#""issuanceRewards": true"#, r#""issuanceRewards": false"#)
+            .replace(
+                r#""services": {}"#,
+                r#""services": { "firehose": ["eth.firehose.example:443", "eth.firehose.other:443"] },
+                "graphNode": { "protocol": "ethereum", "deprecatedAt": "2025-06-01T00:00:00Z" }
+            },
+            {
+                "id": "sepolia",
+                "fullName": "Ethereum Sepolia",
+                "shortName": "Sepolia",
+                "caip2Id": "eip155:11155111",
+                "networkType": "testnet",
+                "issuanceRewards": false,
+                "services": {}"#,
+            );
+        let new = NetworksRegistry::from_json(&new_json).unwrap();
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.registry,
+            vec![FieldChange {
+                field: "version".to_string(),
+                old: json!("x.x.x"),
+                new: json!("x.x.y"),
+            }]
+        );
+        assert_eq!(diff.added, vec!["sepolia"]);
+        assert!(diff.removed.is_empty());
+        let fields: Vec<&str> = diff.changed["mainnet"].iter().map(|change| change.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec!["aliases", "graphNode.deprecatedAt", "issuanceRewards", "services.firehose"]
+        );
+
+        assert_eq!(
+            diff.to_string(),
+            r#"version: "x.x.x" -> "x.x.y"
++ sepolia
+~ mainnet
+    aliases: added "eth-mainnet"; removed "ethereum"
+    graphNode.deprecatedAt: null -> "2025-06-01T00:00:00Z"
+    issuanceRewards: true -> false
+    services.firehose: added "eth.firehose.other:443"
+"#
+        );
+
+        let value = serde_json::to_value(&diff).unwrap();
+        assert_eq!(value["added"], json!(["sepolia"]));
+        assert_eq!(
+            value["changed"]["mainnet"][2],
+            json!({ "field": "issuanceRewards", "old": true, "new": false })
+        );
+        assert_eq!(serde_json::from_value::<RegistryDiff>(value).unwrap(), diff);
+
+        let reverse = new.diff(&old);
+        assert_eq!(reverse.removed, vec!["sepolia"]);
+        assert!(reverse.added.is_empty());
+    }
+
+    #[test]
+    fn test_diff_sample_registry() {
+        let registry = NetworksRegistry::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/../../sample/TheGraphNetworksRegistry.json"))
+            .expect("Failed to load sample registry");
+        let diff = registry.diff(&registry.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "no changes\n");
+    }
+}
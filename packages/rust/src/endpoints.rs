@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::env;
+
+use crate::types::*;
+
+/// Placeholder for a private API key in `rpc_urls` and `api_urls`
+pub const CUSTOM_API_KEY: &str = "CUSTOM_API_KEY";
+
+/// Supplies API keys for the placeholders in a network's URLs
+///
+/// Placeholders are written `{NAME}` or `${NAME}`, e.g. `{CUSTOM_API_KEY}` or `${INFURA_API_KEY}`,
+/// and the provider is asked for each `NAME`. Keys are returned as is and percent-encoded when
+/// substituted, so keys with `/`, `?` or `&` stay in the part of the URL they were put in. Implemented for:
+///
+/// - `HashMap<String, String>` - keys by placeholder name, the same for every network
+/// - [`EnvApiKeys`] - keys from environment variables, optionally per network id
+/// - closures `Fn(&Network, &str) -> Option<String>` taking the network and placeholder name
+pub trait ApiKeyProvider {
+    /// Returns the key to substitute for `placeholder` in the URLs of `network`
+    fn api_key(&self, network: &Network, placeholder: &str) -> Option<String>;
+}
+
+impl ApiKeyProvider for HashMap<String, String> {
+    fn api_key(&self, _network: &Network, placeholder: &str) -> Option<String> {
+        self.get(placeholder).cloned()
+    }
+}
+
+impl<F> ApiKeyProvider for F
+where
+    F: Fn(&Network, &str) -> Option<String>,
+{
+    fn api_key(&self, network: &Network, placeholder: &str) -> Option<String> {
+        self(network, placeholder)
+    }
+}
+
+/// Reads API keys from environment variables
+///
+/// For `{CUSTOM_API_KEY}` in the URLs of `arbitrum-one`, looks up `CUSTOM_API_KEY_ARBITRUM_ONE`
+/// first and then `CUSTOM_API_KEY`. Network ids are uppercased with other characters replaced by `_`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvApiKeys;
+
+impl ApiKeyProvider for EnvApiKeys {
+    fn api_key(&self, network: &Network, placeholder: &str) -> Option<String> {
+        let network_id: String = network
+            .id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+            .collect();
+        env::var(format!("{}_{}", placeholder, network_id))
+            .or_else(|_| env::var(placeholder))
+            .ok()
+    }
+}
+
+/// A URL with its API key placeholders filled in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    /// URL with every placeholder that a key was provided for substituted
+    pub url: String,
+
+    /// Names of the placeholders left in `url` because no key was provided
+    pub missing_keys: Vec<String>,
+}
+
+/// An explorer API URL with its API key placeholders filled in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiEndpoint {
+    /// Kind of API
+    pub kind: ApiUrlKind,
+
+    pub endpoint: Endpoint,
+}
+
+/// Turns the RPC and explorer API URLs of networks into concrete URLs
+///
+/// # Example
+///
+/// ```
+/// use graph_networks_registry::{EndpointResolver, EnvApiKeys, NetworksRegistry};
+///
+/// # fn example(registry: &NetworksRegistry) {
+/// let resolver = EndpointResolver::new(EnvApiKeys);
+/// if let Some(mainnet) = registry.get_network_by_graph_id("mainnet") {
+///     for endpoint in resolver.rpc_urls(mainnet) {
+///         if endpoint.is_resolved() {
+///             println!("{}", endpoint.url);
+///         } else {
+///             println!("{} needs {:?}", endpoint.url, endpoint.missing_keys);
+///         }
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct EndpointResolver<P> {
+    keys: P,
+}
+
+impl Endpoint {
+    /// Returns `true` if every placeholder was filled in and the URL is ready to use
+    pub fn is_resolved(&self) -> bool {
+        self.missing_keys.is_empty()
+    }
+}
+
+impl<P: ApiKeyProvider> EndpointResolver<P> {
+    /// Creates a resolver that takes API keys from `keys`
+    pub fn new(keys: P) -> Self {
+        EndpointResolver { keys }
+    }
+
+    /// Resolves the `rpc_urls` of `network`
+    pub fn rpc_urls(&self, network: &Network) -> Vec<Endpoint> {
+        network.rpc_urls.iter().flatten().map(|url| self.resolve(network, url)).collect()
+    }
+
+    /// Resolves the explorer `api_urls` of `network`
+    pub fn api_urls(&self, network: &Network) -> Vec<ApiEndpoint> {
+        network
+            .api_urls
+            .iter()
+            .flatten()
+            .map(|api_url| ApiEndpoint {
+                kind: api_url.kind.clone(),
+                endpoint: self.resolve(network, &api_url.url),
+            })
+            .collect()
+    }
+
+    /// Fills in the placeholders of any `url` of `network`
+    pub fn resolve(&self, network: &Network, url: &str) -> Endpoint {
+        let mut resolved = String::with_capacity(url.len());
+        let mut missing_keys: Vec<String> = Vec::new();
+        let mut rest = url;
+        while let Some((start, name, end)) = find_placeholder(rest) {
+            resolved.push_str(&rest[..start]);
+            match self.keys.api_key(network, name) {
+                Some(key) => percent_encode(&key, &mut resolved),
+                None => {
+                    resolved.push_str(&rest[start..end]);
+                    if !missing_keys.iter().any(|missing| missing == name) {
+                        missing_keys.push(name.to_string());
+                    }
+                }
+            }
+            rest = &rest[end..];
+        }
+        resolved.push_str(rest);
+        Endpoint {
+            url: resolved,
+            missing_keys,
+        }
+    }
+}
+
+/// Finds the first `{NAME}` or `${NAME}` placeholder, returning its start, name and end
+fn find_placeholder(url: &str) -> Option<(usize, &str, usize)> {
+    let mut offset = 0;
+    while let Some(open) = url[offset..].find('{').map(|pos| offset + pos) {
+        let name_len = url[open + 1..]
+            .bytes()
+            .take_while(|&b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_')
+            .count();
+        let close = open + 1 + name_len;
+        if name_len > 0 && url.as_bytes().get(close) == Some(&b'}') {
+            let start = if url[..open].ends_with('$') { open - 1 } else { open };
+            return Some((start, &url[open + 1..close], close + 1));
+        }
+        offset = open + 1;
+    }
+    None
+}
+
+/// Appends `value` to `out`, percent-encoding everything but the unreserved characters of RFC 3986
+fn percent_encode(value: &str, out: &mut String) {
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::REGISTRY_JSON;
+
+    fn mainnet() -> Network {
+        let json = REGISTRY_JSON.replace(
+            r#""issuanceRewards""#,
+            r#""rpcUrls": [
+                "https://eth.example/rpc/{CUSTOM_API_KEY}",
+                "https://mainnet.infura.io/v3/${INFURA_API_KEY}?backup={CUSTOM_API_KEY}",
+                "https://public.example/{not-a-placeholder}"
+            ],
+            "apiUrls": [{ "kind": "etherscan", "url": "https://api.etherscan.io/api?apikey={CUSTOM_API_KEY}" }],
+            "issuanceRewards""#,
+        );
+        NetworksRegistry::from_json(&json).unwrap().networks.remove(0)
+    }
+
+    #[test]
+    fn test_resolve_endpoints() {
+        let network = mainnet();
+
+        let keys = HashMap::from([(CUSTOM_API_KEY.to_string(), "secret".to_string())]);
+        let resolver = EndpointResolver::new(keys);
+        let endpoints = resolver.rpc_urls(&network);
+        assert_eq!(endpoints[0].url, "https://eth.example/rpc/secret");
+        assert!(endpoints[0].is_resolved());
+        assert_eq!(endpoints[1].url, "https://mainnet.infura.io/v3/${INFURA_API_KEY}?backup=secret");
+        assert_eq!(endpoints[1].missing_keys, vec!["INFURA_API_KEY"]);
+        assert_eq!(endpoints[2].url, "https://public.example/{not-a-placeholder}");
+        assert!(endpoints[2].is_resolved());
+
+        let api_endpoints = resolver.api_urls(&network);
+        assert_eq!(api_endpoints[0].kind, ApiUrlKind::Etherscan);
+        assert_eq!(api_endpoints[0].endpoint.url, "https://api.etherscan.io/api?apikey=secret");
+
+        let resolver = EndpointResolver::new(|network: &Network, placeholder: &str| {
+            (placeholder == "INFURA_API_KEY").then(|| format!("{}-key", network.id))
+        });
+        let endpoint = resolver.resolve(&network, "https://mainnet.infura.io/v3/${INFURA_API_KEY}?backup={CUSTOM_API_KEY}");
+        assert_eq!(endpoint.url, "https://mainnet.infura.io/v3/mainnet-key?backup={CUSTOM_API_KEY}");
+        assert_eq!(endpoint.missing_keys, vec![CUSTOM_API_KEY]);
+
+        // keys are percent-encoded so they can't change the structure of the URL
+        let keys = HashMap::from([(CUSTOM_API_KEY.to_string(), "a/b?c=d&e f~é".to_string())]);
+        let endpoint = EndpointResolver::new(keys).resolve(&network, "https://eth.example/rpc/{CUSTOM_API_KEY}?x=1");
+        assert_eq!(endpoint.url, "https://eth.example/rpc/a%2Fb%3Fc%3Dd%26e%20f~%C3%A9?x=1");
+    }
+
+    #[test]
+    fn test_env_api_keys() {
+        let network = mainnet();
+        env::set_var("ENDPOINTS_TEST_KEY", "shared");
+        env::set_var("ENDPOINTS_TEST_KEY_MAINNET", "mainnet-only");
+        env::set_var("ENDPOINTS_TEST_OTHER_KEY", "other");
+
+        let resolver = EndpointResolver::new(EnvApiKeys);
+        let endpoint = resolver.resolve(&network, "https://rpc.example/{ENDPOINTS_TEST_KEY}/${ENDPOINTS_TEST_OTHER_KEY}");
+        assert_eq!(endpoint.url, "https://rpc.example/mainnet-only/other");
+        let endpoint = resolver.resolve(&network, "https://rpc.example/{ENDPOINTS_TEST_UNSET_KEY}");
+        assert_eq!(endpoint.missing_keys, vec!["ENDPOINTS_TEST_UNSET_KEY"]);
+    }
+}
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::*;
+
+/// Implements string conversions and serde for an enum with an `Unknown(String)` catch-all
+///
+/// Values not listed deserialize to `Unknown` and serialize back unchanged, so a registry using a
+/// newer schema still loads. The enums are generated in `types.rs`, a variant added by the schema
+/// fails to compile here until its value is listed.
+macro_rules! impl_string_enum {
+    ($($type:ident { $($variant:ident => $value:literal),* $(,)? })*) => {
+        $(
+            impl $type {
+                /// Values known to this version of the crate, as they appear in the registry
+                pub const VALUES: &'static [&'static str] = &[$($value),*];
+
+                /// Returns the value as it appears in the registry
+                pub fn as_str(&self) -> &str {
+                    match self {
+                        $($type::$variant => $value,)*
+                        $type::Unknown(value) => value,
+                    }
+                }
+
+                /// Returns `true` if the value isn't known to this version of the crate
+                pub fn is_unknown(&self) -> bool {
+                    matches!(self, $type::Unknown(_))
+                }
+            }
+
+            impl From<&str> for $type {
+                fn from(value: &str) -> Self {
+                    match value {
+                        $($value => $type::$variant,)*
+                        _ => $type::Unknown(value.to_string()),
+                    }
+                }
+            }
+
+            impl std::str::FromStr for $type {
+                type Err = std::convert::Infallible;
+
+                fn from_str(value: &str) -> Result<Self, Self::Err> {
+                    Ok(value.into())
+                }
+            }
+
+            impl std::fmt::Display for $type {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(self.as_str())
+                }
+            }
+
+            impl Serialize for $type {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_str(self.as_str())
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $type {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let value = String::deserialize(deserializer)?;
+                    Ok(value.as_str().into())
+                }
+            }
+        )*
+    };
+}
+
+impl_string_enum! {
+    ApiUrlKind {
+        Blockscout => "blockscout",
+        Etherscan => "etherscan",
+        Ethplorer => "ethplorer",
+        Other => "other",
+        Subscan => "subscan",
+    }
+    BytesEncoding {
+        Base58 => "base58",
+        Base64 => "base64",
+        Hex => "hex",
+        Other => "other",
+        The0Xhex => "0xhex",
+    }
+    Protocol {
+        Arweave => "arweave",
+        Cosmos => "cosmos",
+        Ethereum => "ethereum",
+        Near => "near",
+        Other => "other",
+        Starknet => "starknet",
+    }
+    NetworkType {
+        Beacon => "beacon",
+        Devnet => "devnet",
+        Mainnet => "mainnet",
+        Testnet => "testnet",
+    }
+    RelationKind {
+        BeaconOf => "beaconOf",
+        EvmOf => "evmOf",
+        ForkedFrom => "forkedFrom",
+        L2Of => "l2Of",
+        Other => "other",
+        ShardOf => "shardOf",
+        SvmOf => "svmOf",
+        TestnetOf => "testnetOf",
+    }
+    Feature {
+        Dexes => "dexes",
+        Nfts => "nfts",
+        Other => "other",
+        Tokens => "tokens",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::REGISTRY_JSON;
+
+    #[test]
+    fn test_unknown_enum_values() {
+        let json = REGISTRY_JSON
+            .replace(r#""networkType": "mainnet""#, r#""networkType": "sidechain""#)
+            .replace(
+                r#""issuanceRewards""#,
+                r#""relations": [{ "kind": "l2Of", "network": "mainnet" }, { "kind": "rollupOf", "network": "mainnet" }], "issuanceRewards""#,
+            );
+        let registry = NetworksRegistry::from_json(&json).expect("Unknown enum values should not fail parsing");
+        let network = &registry.networks[0];
+        assert_eq!(network.network_type, NetworkType::Unknown("sidechain".to_string()));
+        assert!(network.network_type.is_unknown());
+
+        let relations = network.relations.as_ref().unwrap();
+        assert_eq!(relations[0].kind, RelationKind::L2Of);
+        assert_eq!(relations[1].kind.as_str(), "rollupOf");
+
+        // Unknown values serialize back unchanged
+        let value = serde_json::to_value(&registry).unwrap();
+        assert_eq!(value["networks"][0]["networkType"], "sidechain");
+        assert_eq!(value["networks"][0]["relations"][0]["kind"], "l2Of");
+        assert_eq!(value["networks"][0]["relations"][1]["kind"], "rollupOf");
+
+        assert_eq!("0xhex".parse(), Ok(BytesEncoding::The0Xhex));
+        assert_eq!(Feature::Nfts.to_string(), "nfts");
+        assert_eq!(ApiUrlKind::from("other"), ApiUrlKind::Other);
+        assert_eq!(Protocol::from("solana"), Protocol::Unknown("solana".to_string()));
+
+        for &value in NetworkType::VALUES {
+            assert!(!NetworkType::from(value).is_unknown());
+            assert_eq!(NetworkType::from(value).as_str(), value);
+        }
+        assert_eq!(Protocol::VALUES.len(), 6);
+        assert!(RelationKind::VALUES.contains(&"l2Of"));
+    }
+}
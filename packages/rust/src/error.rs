@@ -1,6 +1,8 @@
 use std::io;
 use thiserror::Error;
 
+use crate::validation::ValidationIssue;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("IO error: {0}")]
@@ -9,7 +11,92 @@ pub enum Error {
     #[error("Parse error: {0}")]
     Parse(#[from] serde_json::Error),
 
-    #[cfg(feature = "fetch")]
+    #[cfg(feature = "yaml")]
+    #[error("YAML parse error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[cfg(feature = "toml")]
+    #[error("TOML parse error: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    /// Parse error in a document loaded from a file or URL
+    #[error("Parse error in {location}: {source}")]
+    ParseAt { location: String, source: serde_json::Error },
+
+    /// The input is compressed with a format whose feature is not enabled
+    #[error("Input is {format} compressed, enable the {feature} feature to read it")]
+    UnsupportedCompression { format: &'static str, feature: &'static str },
+
+    #[error("Invalid {part} in CAIP id \"{value}\": {reason}")]
+    InvalidCaipId {
+        value: String,
+        part: &'static str,
+        reason: &'static str,
+    },
+
+    #[error("Invalid registry version \"{value}\": {reason}")]
+    InvalidVersion { value: String, reason: &'static str },
+
+    /// A version listing was readable but didn't name any published registry file
+    #[error("No registry versions listed at {location}")]
+    NoVersionsListed { location: String },
+
+    /// An overlay can't be applied to the registry it is merged into
+    #[error("Invalid overlay {overlay} for network \"{network}\": {reason}")]
+    InvalidOverlay { overlay: String, network: String, reason: String },
+
+    /// A builder is missing a field that has no default
+    #[error("Missing required field {field} of {type_name}")]
+    MissingField { type_name: &'static str, field: &'static str },
+
+    /// Validation of a built value found problems
+    #[error("Invalid {type_name}: {}", format_issues(.issues))]
+    Invalid {
+        type_name: &'static str,
+        issues: Vec<ValidationIssue>,
+    },
+
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
+    /// The registry document has a different schema version than this crate supports
+    #[error("Schema version mismatch: expected {expected}, found {found}")]
+    SchemaVersionMismatch { expected: String, found: String },
+
+    #[cfg(any(feature = "fetch", feature = "blocking"))]
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
+
+    /// The server answered with a non-success status code
+    #[error("HTTP status {status} from {url}")]
+    HttpStatus { url: String, status: u16 },
+
+    /// Every source failed, with the error of each attempted URL in the order tried
+    #[error("All registry sources failed: {}", format_attempts(.attempts))]
+    AllSourcesFailed { attempts: Vec<(String, Error)> },
+}
+
+impl Error {
+    /// Wraps a parse error with the file or URL the document came from
+    pub(crate) fn at(self, location: impl Into<String>) -> Self {
+        match self {
+            Error::Parse(source) => Error::ParseAt {
+                location: location.into(),
+                source,
+            },
+            err => err,
+        }
+    }
+}
+
+fn format_attempts(attempts: &[(String, Error)]) -> String {
+    attempts
+        .iter()
+        .map(|(url, err)| format!("{}: {}", url, err))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn format_issues(issues: &[ValidationIssue]) -> String {
+    issues.iter().map(ValidationIssue::to_string).collect::<Vec<_>>().join("; ")
 }
@@ -0,0 +1,114 @@
+use serde_json::{Map, Value};
+
+use crate::types::*;
+
+impl NetworksRegistry {
+    /// Lists the fields that aren't known to this version of the crate
+    ///
+    /// Unknown fields are kept in the `extra` map of each struct and serialized back unchanged.
+    /// A non-empty list usually means the registry was published with a newer schema.
+    ///
+    /// # Returns
+    ///
+    /// Paths to the fields using their JSON names, with networks identified by id,
+    /// e.g. `networks[mainnet].firehose.newField`
+    pub fn unknown_fields(&self) -> Vec<String> {
+        let mut fields = Vec::new();
+        push_fields(&mut fields, "", &self.extra);
+
+        for network in &self.networks {
+            let path = format!("networks[{}]", network.id);
+            push_fields(&mut fields, &path, &network.extra);
+
+            for (i, api_url) in network.api_urls.iter().flatten().enumerate() {
+                push_fields(&mut fields, &format!("{}.apiUrls[{}]", path, i), &api_url.extra);
+            }
+            if let Some(firehose) = &network.firehose {
+                push_fields(&mut fields, &format!("{}.firehose", path), &firehose.extra);
+                if let Some(block) = &firehose.first_streamable_block {
+                    push_fields(&mut fields, &format!("{}.firehose.firstStreamableBlock", path), &block.extra);
+                }
+            }
+            if let Some(graph_node) = &network.graph_node {
+                push_fields(&mut fields, &format!("{}.graphNode", path), &graph_node.extra);
+            }
+            if let Some(icon) = &network.icon {
+                push_fields(&mut fields, &format!("{}.icon", path), &icon.extra);
+                if let Some(web3_icons) = &icon.web3_icons {
+                    push_fields(&mut fields, &format!("{}.icon.web3Icons", path), &web3_icons.extra);
+                }
+            }
+            for (i, docs_url) in network.indexer_docs_urls.iter().flatten().enumerate() {
+                push_fields(&mut fields, &format!("{}.indexerDocsUrls[{}]", path, i), &docs_url.extra);
+            }
+            for (i, relation) in network.relations.iter().flatten().enumerate() {
+                push_fields(&mut fields, &format!("{}.relations[{}]", path, i), &relation.extra);
+            }
+            push_fields(&mut fields, &format!("{}.services", path), &network.services.extra);
+            if let Some(token_api) = &network.token_api {
+                push_fields(&mut fields, &format!("{}.tokenApi", path), &token_api.extra);
+            }
+        }
+
+        fields
+    }
+}
+
+fn push_fields(fields: &mut Vec<String>, path: &str, extra: &Map<String, Value>) {
+    fields.extend(extra.keys().map(|key| match path {
+        "" => key.clone(),
+        _ => format!("{}.{}", path, key),
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::tests::REGISTRY_JSON;
+    use crate::types::NetworksRegistry;
+
+    #[test]
+    fn test_unknown_fields() {
+        let json = REGISTRY_JSON
+            .replace(r#""version": "x.x.x","#, r#""version": "x.x.x", "maintainers": ["pinax"],"#)
+            .replace(r#""services": {}"#, r#""services": { "rpc": ["https://rpc.example"] }"#)
+            .replace(
+                r#""issuanceRewards""#,
+                r#""relations": [{ "kind": "l2Of", "network": "mainnet", "since": 1 }], "genesisHash": "0xd4e5", "issuanceRewards""#,
+            );
+        let registry = NetworksRegistry::from_json(&json).unwrap();
+        assert_eq!(
+            registry.unknown_fields(),
+            vec![
+                "maintainers",
+                "networks[mainnet].genesisHash",
+                "networks[mainnet].relations[0].since",
+                "networks[mainnet].services.rpc",
+            ]
+        );
+
+        // Unknown fields survive a round trip
+        let value = serde_json::to_value(&registry).unwrap();
+        assert_eq!(value["maintainers"], serde_json::json!(["pinax"]));
+        assert_eq!(value["networks"][0]["genesisHash"], "0xd4e5");
+        assert_eq!(value["networks"][0]["relations"][0]["since"], 1);
+        assert_eq!(value["networks"][0]["services"]["rpc"][0], "https://rpc.example");
+        let reparsed: NetworksRegistry = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(reparsed.unknown_fields(), registry.unknown_fields());
+
+        // The whole document comes back as it was read, without added nulls
+        assert_eq!(value, serde_json::from_str::<serde_json::Value>(&json).unwrap());
+
+        let registry = NetworksRegistry::from_json(REGISTRY_JSON).unwrap();
+        assert!(registry.unknown_fields().is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_sample() {
+        let json = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../sample/TheGraphNetworksRegistry.json")).unwrap();
+        let registry = NetworksRegistry::from_json(&json).unwrap();
+        assert_eq!(
+            serde_json::to_value(&registry).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use std::io::{self, Read};
+
+use crate::error::Error;
+use crate::overlay::RegistryOverlay;
+use crate::types::NetworksRegistry;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+impl NetworksRegistry {
+    /// Creates a new NetworksRegistry from a YAML string
+    ///
+    /// Uses the same field names as the JSON registry, with timestamps as strings.
+    ///
+    /// # Arguments
+    ///
+    /// * `yaml` - A YAML string containing the networks registry data
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Yaml` if the YAML is invalid or doesn't match the expected format
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, Error> {
+        let registry: NetworksRegistry =
+            serde_yaml::from_str(yaml).inspect_err(|_err| debug!(error = %_err, "failed to parse registry"))?;
+        Ok(indexed(registry))
+    }
+
+    /// Creates a new NetworksRegistry from a TOML string
+    ///
+    /// Uses the same field names as the JSON registry, with `"$schema"` quoted, networks as
+    /// `[[networks]]` tables and timestamps as strings.
+    ///
+    /// # Arguments
+    ///
+    /// * `toml` - A TOML string containing the networks registry data
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Toml` if the TOML is invalid or doesn't match the expected format
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, Error> {
+        let registry: NetworksRegistry = toml::from_str(toml).inspect_err(|_err| debug!(error = %_err, "failed to parse registry"))?;
+        Ok(indexed(registry))
+    }
+
+    /// Creates a new NetworksRegistry from a reader of JSON data
+    ///
+    /// The data is deserialized while it is read, without loading the whole document first.
+    ///
+    /// gzip and zstd compressed input is detected from its first bytes and decompressed while
+    /// parsing, with the `gzip` and `zstd` features.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader of plain, gzip or zstd compressed JSON, e.g. a `File`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be read or decompressed, or contains invalid JSON.
+    /// Returns `Error::UnsupportedCompression` if the data is compressed and the feature to
+    /// decompress it is not enabled
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut magic = [0; 4];
+        let len = read_prefix(&mut reader, &mut magic)?;
+        let reader = (&magic[..len]).chain(reader);
+
+        let magic = &magic[..len];
+        if magic.starts_with(GZIP_MAGIC) {
+            from_json_reader(gzip_decoder(reader)?)
+        } else if magic.starts_with(ZSTD_MAGIC) {
+            from_json_reader(zstd_decoder(reader)?)
+        } else {
+            from_json_reader(reader)
+        }
+    }
+}
+
+impl RegistryOverlay {
+    /// Creates an overlay from a YAML string, with the same fields as a JSON overlay
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Yaml` if the YAML is invalid or doesn't match the expected format
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, Error> {
+        Ok(serde_yaml::from_str(yaml)?)
+    }
+
+    /// Creates an overlay from a TOML string, with the same fields as a JSON overlay
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Toml` if the TOML is invalid or doesn't match the expected format
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, Error> {
+        Ok(toml::from_str(toml)?)
+    }
+}
+
+fn indexed(registry: NetworksRegistry) -> NetworksRegistry {
+    registry.index.get(&registry.networks);
+    registry
+}
+
+fn from_json_reader<R: Read>(reader: R) -> Result<NetworksRegistry, Error> {
+    let registry: NetworksRegistry =
+        serde_json::from_reader(io::BufReader::new(reader)).inspect_err(|_err| debug!(error = %_err, "failed to parse registry"))?;
+    Ok(indexed(registry))
+}
+
+/// Reads up to `buf.len()` bytes, fewer only at the end of the input
+fn read_prefix<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(len)
+}
+
+#[cfg(feature = "gzip")]
+fn gzip_decoder<R: Read>(reader: R) -> Result<impl Read, Error> {
+    Ok(flate2::read::MultiGzDecoder::new(reader))
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gzip_decoder<R: Read>(_reader: R) -> Result<io::Empty, Error> {
+    Err(Error::UnsupportedCompression {
+        format: "gzip",
+        feature: "gzip",
+    })
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_decoder<R: Read>(reader: R) -> Result<impl Read, Error> {
+    ruzstd::StreamingDecoder::new(reader).map_err(|err| Error::Io(io::Error::new(io::ErrorKind::InvalidData, err)))
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_decoder<R: Read>(_reader: R) -> Result<io::Empty, Error> {
+    Err(Error::UnsupportedCompression {
+        format: "zstd",
+        feature: "zstd",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::REGISTRY_JSON;
+
+    fn expected() -> String {
+        NetworksRegistry::from_json(REGISTRY_JSON).unwrap().to_json().unwrap()
+    }
+
+    /// Wraps data in a zstd frame of one uncompressed block
+    fn zstd_frame(data: &[u8]) -> Vec<u8> {
+        let mut frame = ZSTD_MAGIC.to_vec();
+        // single segment with a 4 byte content size
+        frame.push(0xa0);
+        frame.extend((data.len() as u32).to_le_bytes());
+        // last raw block
+        frame.extend(&((data.len() as u32) << 3 | 1).to_le_bytes()[..3]);
+        frame.extend(data);
+        frame
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let registry = NetworksRegistry::from_reader(REGISTRY_JSON.as_bytes()).unwrap();
+        assert_eq!(registry.to_json().unwrap(), expected());
+        assert_eq!(registry.get_network_by_graph_id("eth").unwrap().id, "mainnet");
+
+        assert!(matches!(NetworksRegistry::from_reader(&b"{"[..]), Err(Error::Parse(_))));
+        assert!(matches!(NetworksRegistry::from_reader(io::empty()), Err(Error::Parse(_))));
+
+        // parsed while reading, so errors point at the problem
+        let json = REGISTRY_JSON.replace(r#""networkType": "mainnet""#, r#""networkType": 1"#);
+        let line = json.lines().position(|line| line.contains(r#""networkType": 1"#)).unwrap() + 1;
+        assert!(matches!(NetworksRegistry::from_reader(json.as_bytes()), Err(Error::Parse(err)) if err.line() == line && err.column() > 0));
+
+        #[cfg(not(feature = "gzip"))]
+        assert!(matches!(
+            NetworksRegistry::from_reader(&[GZIP_MAGIC, b"\x08\x00"].concat()[..]),
+            Err(Error::UnsupportedCompression { feature: "gzip", .. })
+        ));
+        #[cfg(not(feature = "zstd"))]
+        assert!(matches!(
+            NetworksRegistry::from_reader(&zstd_frame(REGISTRY_JSON.as_bytes())[..]),
+            Err(Error::UnsupportedCompression { feature: "zstd", .. })
+        ));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_from_reader_gzip() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(REGISTRY_JSON.as_bytes()).unwrap();
+        let gzip = encoder.finish().unwrap();
+        assert_eq!(NetworksRegistry::from_reader(&gzip[..]).unwrap().to_json().unwrap(), expected());
+
+        assert!(NetworksRegistry::from_reader(&gzip[..gzip.len() / 2]).is_err());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_from_reader_zstd() {
+        let zstd = zstd_frame(REGISTRY_JSON.as_bytes());
+        assert_eq!(NetworksRegistry::from_reader(&zstd[..]).unwrap().to_json().unwrap(), expected());
+
+        assert!(matches!(NetworksRegistry::from_reader(&zstd[..4]), Err(Error::Io(_))));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_from_yaml() {
+        let yaml = r#"
+$schema: https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_vx_x.json
+version: x.x.x
+title: Test Registry
+description: Test Registry
+updatedAt: 2025-01-01T00:00:00Z
+networks:
+  - id: mainnet
+    fullName: Ethereum Mainnet
+    shortName: Ethereum
+    caip2Id: eip155:1
+    networkType: mainnet
+    aliases: [ethereum, eth]
+    issuanceRewards: true
+    services: {}
+"#;
+        let registry = NetworksRegistry::from_yaml(yaml).unwrap();
+        assert_eq!(registry.to_json().unwrap(), expected());
+        assert_eq!(registry.get_network_by_graph_id("eth").unwrap().id, "mainnet");
+
+        assert!(matches!(NetworksRegistry::from_yaml("networks: 1"), Err(Error::Yaml(_))));
+
+        let overlay = RegistryOverlay::from_yaml("remove: [goerli]\naliases:\n  mainnet: [l1]").unwrap();
+        assert_eq!(overlay.remove, ["goerli"]);
+        assert_eq!(overlay.aliases["mainnet"], ["l1"]);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_from_toml() {
+        let toml = r#"
+"$schema" = "https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_vx_x.json"
+version = "x.x.x"
+title = "Test Registry"
+description = "Test Registry"
+updatedAt = "2025-01-01T00:00:00Z"
+
+[[networks]]
+id = "mainnet"
+fullName = "Ethereum Mainnet"
+shortName = "Ethereum"
+caip2Id = "eip155:1"
+networkType = "mainnet"
+aliases = ["ethereum", "eth"]
+issuanceRewards = true
+services = {}
+"#;
+        let registry = NetworksRegistry::from_toml(toml).unwrap();
+        assert_eq!(registry.to_json().unwrap(), expected());
+        assert_eq!(registry.get_network_by_graph_id("eth").unwrap().id, "mainnet");
+
+        assert!(matches!(NetworksRegistry::from_toml("networks = 1"), Err(Error::Toml(_))));
+
+        let overlay = RegistryOverlay::from_toml("remove = [\"goerli\"]\n[patch.mainnet]\nrpcUrls = [\"https://rpc.internal\"]").unwrap();
+        assert_eq!(overlay.remove, ["goerli"]);
+        assert_eq!(overlay.patch["mainnet"]["rpcUrls"][0], "https://rpc.internal");
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::types::Network;
+
+/// Hash map lookup tables over `NetworksRegistry::networks`, mapping keys to positions in the list
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NetworksIndex {
+    len: usize,
+    by_id: HashMap<String, usize>,
+    by_alias: HashMap<String, usize>,
+    /// By CAIP-2 namespace and then reference, so parsed and unparsed chain ids can be looked up without allocating
+    by_caip2_id: HashMap<String, HashMap<String, usize>>,
+}
+
+impl NetworksIndex {
+    /// Builds the index. When a key appears more than once, the first network in the list wins
+    pub(crate) fn build(networks: &[Network]) -> Self {
+        let mut index = NetworksIndex {
+            len: networks.len(),
+            by_id: HashMap::with_capacity(networks.len()),
+            by_alias: HashMap::new(),
+            by_caip2_id: HashMap::new(),
+        };
+        for (pos, network) in networks.iter().enumerate() {
+            index.by_id.entry(network.id.clone()).or_insert(pos);
+            // malformed CAIP-2 ids can't be looked up, since lookups reject them
+            if let Some((namespace, reference)) = network.caip2_id.split_once(':') {
+                let by_reference = index.by_caip2_id.entry(namespace.to_string()).or_default();
+                by_reference.entry(reference.to_string()).or_insert(pos);
+            }
+            for alias in network.aliases.iter().flatten() {
+                index.by_alias.entry(alias.clone()).or_insert(pos);
+            }
+        }
+        index
+    }
+
+    pub(crate) fn id(&self, id: &str) -> Option<usize> {
+        self.by_id.get(id).copied()
+    }
+
+    pub(crate) fn alias(&self, alias: &str) -> Option<usize> {
+        self.by_alias.get(alias).copied()
+    }
+
+    /// Resolves a graph id: the network with that id, or else the first network with that alias.
+    /// Every lookup by graph id goes through here so they agree when an alias collides with an id
+    pub(crate) fn graph_id(&self, id: &str) -> Option<usize> {
+        self.id(id).or_else(|| self.alias(id))
+    }
+
+    pub(crate) fn caip2_id(&self, namespace: &str, reference: &str) -> Option<usize> {
+        self.by_caip2_id.get(namespace)?.get(reference).copied()
+    }
+
+    /// Returns the network at `pos` if it still satisfies `matches`.
+    ///
+    /// The index is authoritative: a key it doesn't have is not looked for in `networks`. Changes
+    /// that can be spotted cheaply are still handled with a linear scan, i.e. when networks were
+    /// added or removed, or when the network at `pos` no longer matches.
+    pub(crate) fn resolve<'a>(
+        &self,
+        networks: &'a [Network],
+        pos: Option<usize>,
+        matches: impl Fn(&Network) -> bool,
+    ) -> Option<&'a Network> {
+        if self.len != networks.len() {
+            return networks.iter().find(|network| matches(network));
+        }
+        match networks.get(pos?) {
+            Some(network) if matches(network) => Some(network),
+            _ => networks.iter().find(|network| matches(network)),
+        }
+    }
+}
+
+/// Lazily built [`NetworksIndex`], stored alongside the networks it indexes
+#[derive(Clone, Default)]
+pub(crate) struct LazyIndex(OnceLock<NetworksIndex>);
+
+impl LazyIndex {
+    pub(crate) fn get(&self, networks: &[Network]) -> &NetworksIndex {
+        self.0.get_or_init(|| NetworksIndex::build(networks))
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.0 = OnceLock::new();
+    }
+}
+
+impl fmt::Debug for LazyIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyIndex").field("built", &self.0.get().is_some()).finish()
+    }
+}
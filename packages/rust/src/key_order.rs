@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde_json::{Map, Value};
+
+use crate::types::{Network, NetworksRegistry};
+
+/// Keys of the registry in the order of the published schema, used for objects that weren't read
+/// from a file and for keys added after reading
+const REGISTRY_KEYS: &[&str] = &["$schema", "version", "title", "description", "updatedAt", "networks"];
+
+const NETWORK_KEYS: &[&str] = &[
+    "id",
+    "shortName",
+    "secondName",
+    "fullName",
+    "aliases",
+    "caip2Id",
+    "graphNode",
+    "explorerUrls",
+    "rpcUrls",
+    "apiUrls",
+    "services",
+    "networkType",
+    "relations",
+    "issuanceRewards",
+    "nativeToken",
+    "docsUrl",
+    "indexerDocsUrls",
+    "firehose",
+    "tokenApi",
+    "icon",
+];
+
+/// Schema order of the keys of an object, by the key it appears under
+fn schema_keys(key: &str) -> &'static [&'static str] {
+    match key {
+        "networks" => NETWORK_KEYS,
+        "graphNode" => &["protocol", "deprecatedAt"],
+        "apiUrls" => &["url", "kind"],
+        "services" => &["subgraphs", "sps", "firehose", "substreams", "tokenApi"],
+        "indexerDocsUrls" => &["url", "description"],
+        "firehose" => &[
+            "blockType",
+            "evmExtendedModel",
+            "bufUrl",
+            "bytesEncoding",
+            "firstStreamableBlock",
+            "blockFeatures",
+            "deprecatedAt",
+        ],
+        "firstStreamableBlock" => &["id", "height"],
+        "tokenApi" => &["features", "networkId", "deprecatedAt"],
+        "icon" => &["web3Icons"],
+        "web3Icons" => &["name", "variants"],
+        "relations" => &["kind", "network"],
+        _ => &[],
+    }
+}
+
+/// Order of the keys of an object as read, and of the objects nested in it
+#[derive(Clone, Default)]
+pub(crate) struct Order {
+    keys: Vec<String>,
+    fields: HashMap<String, Order>,
+    items: Vec<Order>,
+}
+
+impl Order {
+    fn is_empty(&self) -> bool {
+        self.keys.is_empty() && self.fields.is_empty() && self.items.is_empty()
+    }
+}
+
+/// Order of the keys of a registry as read, so it serializes back the way it was published
+///
+/// Networks are looked up by id, so their keys keep their order when networks are added, removed
+/// or moved around.
+#[derive(Clone, Default)]
+pub(crate) struct KeyOrder {
+    registry: Order,
+    networks: HashMap<String, Order>,
+}
+
+impl KeyOrder {
+    fn new(mut registry: Order, networks: &[Network]) -> Self {
+        let items = registry.fields.remove("networks").map(|order| order.items).unwrap_or_default();
+        let mut orders = HashMap::with_capacity(items.len());
+        for (network, order) in networks.iter().zip(items) {
+            orders.entry(network.id.clone()).or_insert(order);
+        }
+        KeyOrder {
+            registry,
+            networks: orders,
+        }
+    }
+}
+
+impl fmt::Debug for KeyOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyOrder").field("networks", &self.networks.len()).finish()
+    }
+}
+
+/// A document read into a `Value`, along with the order of its keys
+pub(crate) struct Recorded {
+    value: Value,
+    order: Order,
+}
+
+impl Recorded {
+    /// Converts the document into a registry that serializes back in the same order
+    pub(crate) fn into_registry(self) -> Result<NetworksRegistry, serde_json::Error> {
+        let mut registry: NetworksRegistry = serde_json::from_value(self.value)?;
+        registry.key_order = KeyOrder::new(self.order, &registry.networks);
+        Ok(registry)
+    }
+
+    fn leaf(value: Value) -> Self {
+        Recorded {
+            value,
+            order: Order::default(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Recorded {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(RecordedVisitor)
+    }
+}
+
+struct RecordedVisitor;
+
+impl<'de> Visitor<'de> for RecordedVisitor {
+    type Value = Recorded;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Recorded, E> {
+        Ok(Recorded::leaf(Value::Bool(value)))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Recorded, E> {
+        Ok(Recorded::leaf(Value::from(value)))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Recorded, E> {
+        Ok(Recorded::leaf(Value::from(value)))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Recorded, E> {
+        Ok(Recorded::leaf(Value::from(value)))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Recorded, E> {
+        Ok(Recorded::leaf(Value::String(value.to_string())))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Recorded, E> {
+        Ok(Recorded::leaf(Value::String(value)))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Recorded, E> {
+        Ok(Recorded::leaf(Value::Null))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Recorded, E> {
+        Ok(Recorded::leaf(Value::Null))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Recorded, D::Error> {
+        Recorded::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Recorded, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        let mut order = Order::default();
+        while let Some(item) = seq.next_element::<Recorded>()? {
+            values.push(item.value);
+            order.items.push(item.order);
+        }
+        if order.items.iter().all(Order::is_empty) {
+            order.items.clear();
+        }
+        Ok(Recorded {
+            value: Value::Array(values),
+            order,
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Recorded, A::Error> {
+        let mut values = Map::new();
+        let mut order = Order::default();
+        while let Some(key) = map.next_key::<String>()? {
+            let item: Recorded = map.next_value()?;
+            if !item.order.is_empty() {
+                order.fields.insert(key.clone(), item.order);
+            }
+            if values.insert(key.clone(), item.value).is_none() {
+                order.keys.push(key);
+            }
+        }
+        Ok(Recorded {
+            value: Value::Object(values),
+            order,
+        })
+    }
+}
+
+/// Serializes a registry `Value` with its keys in the recorded order, then in schema order
+pub(crate) struct Ordered<'a> {
+    value: &'a Value,
+    key_order: &'a KeyOrder,
+}
+
+impl<'a> Ordered<'a> {
+    pub(crate) fn new(value: &'a Value, key_order: &'a KeyOrder) -> Self {
+        Ordered { value, key_order }
+    }
+}
+
+impl Serialize for Ordered<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Value::Object(registry) = self.value else {
+            return self.value.serialize(serializer);
+        };
+        let mut map = serializer.serialize_map(Some(registry.len()))?;
+        for key in ordered_keys(registry, Some(&self.key_order.registry), REGISTRY_KEYS) {
+            match &registry[key] {
+                Value::Array(networks) if key == "networks" => map.serialize_entry(
+                    key,
+                    &Networks {
+                        networks,
+                        orders: &self.key_order.networks,
+                    },
+                )?,
+                value => map.serialize_entry(
+                    key,
+                    &OrderedValue {
+                        value,
+                        order: self.key_order.registry.fields.get(key),
+                        schema_keys: schema_keys(key),
+                    },
+                )?,
+            }
+        }
+        map.end()
+    }
+}
+
+struct Networks<'a> {
+    networks: &'a [Value],
+    orders: &'a HashMap<String, Order>,
+}
+
+impl Serialize for Networks<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.networks.len()))?;
+        for network in self.networks {
+            seq.serialize_element(&OrderedValue {
+                value: network,
+                order: network["id"].as_str().and_then(|id| self.orders.get(id)),
+                schema_keys: NETWORK_KEYS,
+            })?;
+        }
+        seq.end()
+    }
+}
+
+struct OrderedValue<'a> {
+    value: &'a Value,
+    order: Option<&'a Order>,
+    schema_keys: &'static [&'static str],
+}
+
+impl Serialize for OrderedValue<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.value {
+            Value::Object(object) => {
+                let mut map = serializer.serialize_map(Some(object.len()))?;
+                for key in ordered_keys(object, self.order, self.schema_keys) {
+                    map.serialize_entry(
+                        key,
+                        &OrderedValue {
+                            value: &object[key],
+                            order: self.order.and_then(|order| order.fields.get(key)),
+                            schema_keys: schema_keys(key),
+                        },
+                    )?;
+                }
+                map.end()
+            }
+            Value::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for (i, item) in items.iter().enumerate() {
+                    seq.serialize_element(&OrderedValue {
+                        value: item,
+                        order: self.order.and_then(|order| order.items.get(i)),
+                        schema_keys: self.schema_keys,
+                    })?;
+                }
+                seq.end()
+            }
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+/// Keys of `object` in the recorded order, followed by the other keys of the schema in schema
+/// order and the remaining unknown keys alphabetically
+fn ordered_keys<'a>(object: &'a Map<String, Value>, order: Option<&Order>, schema_keys: &[&str]) -> Vec<&'a String> {
+    let recorded = order.map(|order| order.keys.as_slice()).unwrap_or_default();
+    let is_recorded = |key: &str| recorded.iter().any(|recorded| recorded == key);
+    let mut keys: Vec<&String> = recorded
+        .iter()
+        .filter_map(|key| object.get_key_value(key).map(|(key, _)| key))
+        .collect();
+    keys.extend(
+        schema_keys
+            .iter()
+            .filter(|key| !is_recorded(key))
+            .filter_map(|key| object.get_key_value(*key).map(|(key, _)| key)),
+    );
+    keys.extend(
+        object
+            .keys()
+            .filter(|key| !is_recorded(key) && !schema_keys.contains(&key.as_str())),
+    );
+    keys
+}
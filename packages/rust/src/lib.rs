@@ -2,13 +2,15 @@
 //!
 //! # Main Types
 //!
-//! - [`NetworksRegistry`] - The main struct for managing network configurations
+//! - [`NetworksRegistry`] - The main struct for managing network configurations, written back with
+//!   [`NetworksRegistry::to_json`] and [`NetworksRegistry::to_file`]
 //!
 //! # Example
 //!
 //! ```
 //! use graph_networks_registry::NetworksRegistry;
 //!
+//! # #[cfg(feature = "fetch")]
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 //! // Load the latest registry from online source
 //! let registry = NetworksRegistry::from_latest_version().await?;
@@ -21,12 +23,58 @@
 //!
 //! # Additional Types
 //!
-//! - [`Network`] - Individual network configuration
+//! - [`Network`] - Individual network configuration, built in code with [`Network::builder`]
+//! - [`NetworksRegistryRef`] - A registry borrowed from a JSON string, for a few lookups without copying
+//! - [`Caip2Id`], [`AccountId`], [`AssetId`] - CAIP-2 chain ids, CAIP-10 account ids and CAIP-19 asset ids
+//! - [`RegistryOverlay`] - Private networks and local changes merged on top of a registry
+//! - [`RelationGraph`] - Testnets, L2s, beacon chains and other relations between networks
+//! - [`RegistryVersion`] - Which published version of the registry to fetch
+//! - `RegistryClient` - Fetches the registry from configurable sources (`fetch` or `blocking` feature)
+//! - `SharedRegistry` - Keeps a registry up to date by polling in the background (`fetch` feature)
 
+#[macro_use]
+mod macros;
+
+mod borrowed;
+mod builder;
+#[cfg(any(feature = "fetch", feature = "blocking"))]
+mod cache;
+mod caip;
 mod client;
+mod diff;
+mod endpoints;
+mod enums;
 mod error;
+mod extra;
+mod formats;
+mod index;
+mod key_order;
+mod overlay;
+mod query;
+#[cfg(any(feature = "fetch", feature = "blocking"))]
+mod registry_client;
+mod relations;
+mod serialize;
+#[cfg(feature = "fetch")]
+mod shared;
+mod timestamp;
 mod types;
+mod validation;
 mod version;
 
+pub use borrowed::{NetworkRef, NetworksRegistryRef};
+pub use builder::{FirehoseBuilder, NetworkBuilder, NetworksRegistryBuilder, ServicesBuilder, TokenApiBuilder};
+pub use caip::{AccountId, AssetId, Caip2Id};
+pub use diff::{FieldChange, RegistryDiff};
+pub use endpoints::{ApiEndpoint, ApiKeyProvider, Endpoint, EndpointResolver, EnvApiKeys, CUSTOM_API_KEY};
 pub use error::Error;
+pub use overlay::{MergedRegistry, NetworkOrigin, NetworkSource, RegistryOverlay};
+pub use query::{NetworkQuery, NetworkQueryIter, Service};
+#[cfg(any(feature = "fetch", feature = "blocking"))]
+pub use registry_client::{CacheStatus, FetchedRegistry, RegistryClient, RegistryClientBuilder};
+pub use relations::{DanglingRelation, RelationEdge, RelationGraph};
+#[cfg(feature = "fetch")]
+pub use shared::{RegistryHandle, SharedRegistry};
 pub use types::*;
+pub use validation::ValidationIssue;
+pub use version::{Compatibility, RegistryVersion, RegistryVersionNumber, SchemaVersion};
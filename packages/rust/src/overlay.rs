@@ -0,0 +1,438 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::Error;
+use crate::types::*;
+use crate::validation::ValidationIssue;
+
+/// Changes to apply on top of a registry, e.g. private networks and devnets
+///
+/// Loaded from a JSON file like
+///
+/// ```json
+/// {
+///     "remove": ["goerli"],
+///     "networks": [{ "id": "devnet", "fullName": "Devnet", "caip2Id": "eip155:1337", ... }],
+///     "patch": { "mainnet": { "rpcUrls": ["https://rpc.internal"], "graphNode": { "deprecatedAt": null } } },
+///     "aliases": { "devnet": ["local"] }
+/// }
+/// ```
+///
+/// and applied by [`NetworksRegistry::merge_overlays`] in this order:
+///
+/// 1. `remove` - removes networks by id
+/// 2. `networks` - adds complete networks, whose ids must not exist yet. To replace a network,
+///    remove and add it in the same overlay
+/// 3. `patch` - changes fields of existing networks by id, as a JSON merge patch (RFC 7386):
+///    objects are merged, `null` removes a field and any other value replaces it
+/// 4. `aliases` - adds aliases to networks by id, moving them away from any other network that had them.
+///    To replace all aliases of a network, patch its `aliases` field instead
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RegistryOverlay {
+    /// Name of the overlay recorded in [`NetworkSource`], the path when loaded with `from_file`
+    #[serde(skip)]
+    pub name: String,
+
+    /// Ids of networks to remove
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove: Vec<String>,
+
+    /// Networks to add
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub networks: Vec<Network>,
+
+    /// Field changes of existing networks, by network id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub patch: BTreeMap<String, Map<String, Value>>,
+
+    /// Aliases to add, by network id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, Vec<String>>,
+}
+
+/// A registry with overlays applied, returned by [`NetworksRegistry::merge_overlays`]
+#[derive(Debug, Clone)]
+pub struct MergedRegistry {
+    /// The merged registry
+    pub registry: NetworksRegistry,
+
+    /// Where each network of `registry` came from, by network id
+    pub sources: BTreeMap<String, NetworkSource>,
+
+    /// Ids of the networks removed, with the name of the overlay that removed them
+    pub removed: BTreeMap<String, String>,
+}
+
+/// Where a network of a [`MergedRegistry`] came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkSource {
+    pub origin: NetworkOrigin,
+
+    /// Names of the overlays that patched the network or added aliases to it, in the order applied
+    pub patched_by: Vec<String>,
+}
+
+/// Where a network was first defined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkOrigin {
+    /// The base registry
+    Base,
+    /// The overlay with this name
+    Overlay(String),
+}
+
+impl RegistryOverlay {
+    /// Creates an overlay from a JSON string
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is invalid or has fields other than those of an overlay
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Creates an overlay by reading from a file, named after its path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or contains invalid data.
+    /// Parse errors are reported as `Error::ParseAt` with the path of the file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        let mut overlay = Self::from_json(&contents).map_err(|err| err.at(path.display().to_string()))?;
+        overlay.name = path.display().to_string();
+        Ok(overlay)
+    }
+
+    /// Sets the name recorded in [`NetworkSource`]
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+}
+
+impl NetworksRegistry {
+    /// Applies overlays on top of the registry, in order
+    ///
+    /// Overlays without a name are named `overlay {n}`, counting from 1.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use graph_networks_registry::{NetworksRegistry, RegistryOverlay};
+    ///
+    /// # fn example() -> Result<(), graph_networks_registry::Error> {
+    /// let base = NetworksRegistry::from_file("TheGraphNetworksRegistry.json")?;
+    /// let devnets = RegistryOverlay::from_file("devnets.json")?;
+    /// let merged = base.merge_overlays([&devnets])?;
+    /// if let Some(devnet) = merged.registry.get_network_by_graph_id("devnet") {
+    ///     println!("{} from {:?}", devnet.id, merged.sources[&devnet.id].origin);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidOverlay` if an overlay adds a network that already exists, refers to a
+    /// network that doesn't exist, or leaves the registry with an issue found by
+    /// [`validate`](NetworksRegistry::validate) that it didn't have before, e.g. a malformed CAIP-2 id or
+    /// an alias that is the id of another network. Relations to networks removed by an overlay are only
+    /// issues for the networks that overlay adds or patches.
+    pub fn merge_overlays<'a>(self, overlays: impl IntoIterator<Item = &'a RegistryOverlay>) -> Result<MergedRegistry, Error> {
+        let mut sources: BTreeMap<String, NetworkSource> = self
+            .networks
+            .iter()
+            .map(|network| {
+                let source = NetworkSource {
+                    origin: NetworkOrigin::Base,
+                    patched_by: Vec::new(),
+                };
+                (network.id.clone(), source)
+            })
+            .collect();
+        let mut issues = self.validate();
+        let mut merged = MergedRegistry {
+            registry: self,
+            sources: BTreeMap::new(),
+            removed: BTreeMap::new(),
+        };
+
+        for (n, overlay) in overlays.into_iter().enumerate() {
+            let name = if overlay.name.is_empty() {
+                format!("overlay {}", n + 1)
+            } else {
+                overlay.name.clone()
+            };
+            let invalid = |network: &str, reason: &str| Error::InvalidOverlay {
+                overlay: name.clone(),
+                network: network.to_string(),
+                reason: reason.to_string(),
+            };
+            let networks = &mut merged.registry.networks;
+
+            for id in &overlay.remove {
+                let pos = position(networks, id).ok_or_else(|| invalid(id, "cannot remove a network that doesn't exist"))?;
+                networks.remove(pos);
+                sources.remove(id);
+                merged.removed.insert(id.clone(), name.clone());
+            }
+
+            for network in &overlay.networks {
+                if position(networks, &network.id).is_some() {
+                    return Err(invalid(&network.id, "cannot add a network that already exists, patch it instead"));
+                }
+                networks.push(network.clone());
+                let source = NetworkSource {
+                    origin: NetworkOrigin::Overlay(name.clone()),
+                    patched_by: Vec::new(),
+                };
+                sources.insert(network.id.clone(), source);
+                merged.removed.remove(&network.id);
+            }
+
+            for (id, patch) in &overlay.patch {
+                let pos = position(networks, id).ok_or_else(|| invalid(id, "cannot patch a network that doesn't exist"))?;
+                let mut value = serde_json::to_value(&networks[pos])?;
+                merge_patch(&mut value, &Value::Object(patch.clone()));
+                let patched: Network =
+                    serde_json::from_value(value).map_err(|err| invalid(id, &format!("patch results in an invalid network: {}", err)))?;
+                if patched.id != *id {
+                    return Err(invalid(id, "patch cannot change the network id"));
+                }
+                networks[pos] = patched;
+                record_patch(&mut sources, id, &name);
+            }
+
+            for (id, aliases) in &overlay.aliases {
+                let pos = position(networks, id).ok_or_else(|| invalid(id, "cannot add aliases to a network that doesn't exist"))?;
+                for network in networks.iter_mut().filter(|network| network.id != *id) {
+                    if let Some(existing) = &mut network.aliases {
+                        existing.retain(|alias| !aliases.contains(alias));
+                    }
+                }
+                let existing = networks[pos].aliases.get_or_insert_with(Vec::new);
+                for alias in aliases {
+                    if !existing.contains(alias) {
+                        existing.push(alias.clone());
+                    }
+                }
+                record_patch(&mut sources, id, &name);
+            }
+
+            let previous = std::mem::replace(&mut issues, merged.registry.validate());
+            let written = |network: &str| overlay.patch.contains_key(network) || position(&overlay.networks, network).is_some();
+            for issue in issues.iter().filter(|issue| !previous.contains(issue)) {
+                if let Some((network, reason)) = issue_reason(issue, written) {
+                    return Err(invalid(network, reason));
+                }
+            }
+        }
+
+        merged.registry.reindex();
+        merged.sources = sources;
+        Ok(merged)
+    }
+}
+
+impl MergedRegistry {
+    /// Returns where the network with this id came from
+    pub fn source(&self, id: &str) -> Option<&NetworkSource> {
+        self.sources.get(id)
+    }
+}
+
+fn position(networks: &[Network], id: &str) -> Option<usize> {
+    networks.iter().position(|network| network.id == id)
+}
+
+/// Network and reason of an issue that an overlay introduced, or `None` if it is not an error.
+/// `written` tells whether the overlay added or patched a network.
+fn issue_reason(issue: &ValidationIssue, written: impl Fn(&str) -> bool) -> Option<(&str, &'static str)> {
+    match issue {
+        ValidationIssue::DuplicateId { id } => Some((id, "results in a duplicate network id")),
+        ValidationIssue::AliasCollision { network, .. } => {
+            Some((network, "results in an alias that is the id or an alias of another network"))
+        }
+        ValidationIssue::DuplicateCaip2Id { network, .. } => Some((network, "results in a CAIP-2 id used by another network")),
+        ValidationIssue::MalformedCaip2Id { network, .. } => Some((network, "results in a malformed CAIP-2 id")),
+        ValidationIssue::UnresolvedRelation { network, .. } if written(network) => {
+            Some((network, "results in a relation to a network that is not in the registry"))
+        }
+        ValidationIssue::UnresolvedTokenApiNetwork { network, .. } if written(network) => {
+            Some((network, "results in a Token API network id that is not in the registry"))
+        }
+        ValidationIssue::InvalidTimestamp {
+            network: Some(network), ..
+        } => Some((network, "results in an invalid timestamp")),
+        _ => None,
+    }
+}
+
+fn record_patch(sources: &mut BTreeMap<String, NetworkSource>, id: &str, overlay: &str) {
+    if let Some(source) = sources.get_mut(id) {
+        if source.patched_by.last().map(String::as_str) != Some(overlay) {
+            source.patched_by.push(overlay.to_string());
+        }
+    }
+}
+
+/// Applies a JSON merge patch (RFC 7386) to `target`
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::REGISTRY_JSON;
+
+    const OVERLAY_JSON: &str = r#"{
+        "networks": [
+            {
+                "id": "devnet",
+                "fullName": "Local Devnet",
+                "shortName": "Devnet",
+                "caip2Id": "eip155:1337",
+                "networkType": "devnet",
+                "issuanceRewards": false,
+                "services": {}
+            }
+        ],
+        "patch": {
+            "mainnet": {
+                "rpcUrls": ["https://rpc.internal"],
+                "graphNode": { "protocol": "ethereum" },
+                "shortName": "ETH"
+            }
+        },
+        "aliases": { "devnet": ["local", "eth"] }
+    }"#;
+
+    #[test]
+    fn test_merge_overlays() {
+        let base = NetworksRegistry::from_json(REGISTRY_JSON).unwrap();
+        let overlay = RegistryOverlay::from_json(OVERLAY_JSON).unwrap();
+        let merged = base.clone().merge_overlays([&overlay]).unwrap();
+        let registry = &merged.registry;
+
+        let devnet = registry.get_network_by_caip2_id("eip155:1337").unwrap();
+        assert_eq!(devnet.id, "devnet");
+        assert_eq!(registry.get_network_by_graph_id("local").unwrap().id, "devnet");
+        // "eth" moved from mainnet to the devnet
+        assert_eq!(registry.get_network_by_graph_id("eth").unwrap().id, "devnet");
+
+        let mainnet = registry.get_network_by_graph_id("mainnet").unwrap();
+        assert_eq!(mainnet.aliases, Some(vec!["ethereum".to_string()]));
+        assert_eq!(mainnet.rpc_urls, Some(vec!["https://rpc.internal".to_string()]));
+        assert_eq!(mainnet.graph_node.as_ref().unwrap().protocol, Some(Protocol::Ethereum));
+        assert_eq!(mainnet.short_name, "ETH");
+        assert_eq!(mainnet.full_name, "Ethereum Mainnet");
+
+        assert_eq!(
+            merged.source("mainnet"),
+            Some(&NetworkSource {
+                origin: NetworkOrigin::Base,
+                patched_by: vec!["overlay 1".to_string()],
+            })
+        );
+        assert_eq!(
+            merged.source("devnet").unwrap().origin,
+            NetworkOrigin::Overlay("overlay 1".to_string())
+        );
+
+        let unset = RegistryOverlay::from_json(r#"{ "patch": { "mainnet": { "rpcUrls": null } } }"#).unwrap();
+        let merged = base.clone().merge_overlays([&overlay, &unset]).unwrap();
+        assert_eq!(merged.registry.get_network_by_graph_id("mainnet").unwrap().rpc_urls, None);
+        assert_eq!(merged.source("mainnet").unwrap().patched_by, vec!["overlay 1", "overlay 2"]);
+
+        let removal = RegistryOverlay::from_json(r#"{ "remove": ["mainnet"] }"#).unwrap().named("cleanup");
+        let merged = base.clone().merge_overlays([&overlay, &removal]).unwrap();
+        assert!(merged.registry.get_network_by_graph_id("mainnet").is_none());
+        assert!(merged.registry.get_network_by_graph_id("ethereum").is_none());
+        assert_eq!(merged.source("mainnet"), None);
+        assert_eq!(merged.removed["mainnet"], "cleanup");
+        assert_eq!(merged.registry.networks.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_overlays() {
+        let base = NetworksRegistry::from_json(REGISTRY_JSON).unwrap();
+        let invalid = |json: &str| {
+            let overlay = RegistryOverlay::from_json(json).unwrap().named("bad.json");
+            match base.clone().merge_overlays([&overlay]) {
+                Err(Error::InvalidOverlay { overlay, network, reason }) => {
+                    assert_eq!(overlay, "bad.json");
+                    (network, reason)
+                }
+                result => panic!("expected an invalid overlay, got {:?}", result.map(|merged| merged.sources)),
+            }
+        };
+
+        assert_eq!(invalid(r#"{ "remove": ["goerli"] }"#).0, "goerli");
+        assert_eq!(invalid(r#"{ "patch": { "goerli": { "fullName": "Goerli" } } }"#).0, "goerli");
+        assert_eq!(invalid(r#"{ "aliases": { "goerli": ["gor"] } }"#).0, "goerli");
+        assert_eq!(
+            invalid(r#"{ "patch": { "mainnet": { "id": "ethereum" } } }"#).1,
+            "patch cannot change the network id"
+        );
+        assert_eq!(
+            invalid(r#"{ "patch": { "mainnet": { "caip2Id": null } } }"#).1,
+            "patch results in an invalid network: missing field `caip2Id`"
+        );
+        let duplicate = r#"{ "networks": [{
+            "id": "mainnet", "fullName": "Mainnet", "shortName": "Mainnet", "caip2Id": "eip155:1", "networkType": "mainnet", "issuanceRewards": false, "services": {}
+        }] }"#;
+        assert_eq!(invalid(duplicate).0, "mainnet");
+
+        // patches and networks that leave the registry with new validation issues
+        assert_eq!(
+            invalid(r#"{ "patch": { "mainnet": { "caip2Id": "mainnet" } } }"#),
+            ("mainnet".to_string(), "results in a malformed CAIP-2 id".to_string())
+        );
+        assert_eq!(
+            invalid(r#"{ "patch": { "mainnet": { "graphNode": { "deprecatedAt": "yesterday" } } } }"#).1,
+            "results in an invalid timestamp"
+        );
+        let alias = r#"{ "networks": [{
+            "id": "devnet", "fullName": "Devnet", "shortName": "Devnet", "caip2Id": "eip155:1337", "networkType": "devnet", "issuanceRewards": false, "services": {},
+            "aliases": ["mainnet"]
+        }] }"#;
+        assert_eq!(
+            invalid(alias),
+            (
+                "devnet".to_string(),
+                "results in an alias that is the id or an alias of another network".to_string()
+            )
+        );
+        let relation = r#"{ "patch": { "mainnet": { "relations": [{ "kind": "testnetOf", "network": "goerli" }] } } }"#;
+        assert_eq!(
+            invalid(relation).1,
+            "results in a relation to a network that is not in the registry"
+        );
+
+        // typos in the overlay keys are rejected when parsing
+        assert!(matches!(RegistryOverlay::from_json(r#"{ "patches": {} }"#), Err(Error::Parse(_))));
+    }
+}
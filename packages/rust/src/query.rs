@@ -0,0 +1,447 @@
+use std::slice;
+
+#[cfg(feature = "chrono")]
+use crate::timestamp;
+use crate::types::*;
+
+/// A service listed in [`Services`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Service {
+    Firehose,
+
+    Sps,
+
+    Subgraphs,
+
+    Substreams,
+
+    TokenApi,
+}
+
+impl Service {
+    /// Every service, in the order of the schema
+    pub const ALL: [Service; 5] = [
+        Service::Subgraphs,
+        Service::Sps,
+        Service::Firehose,
+        Service::Substreams,
+        Service::TokenApi,
+    ];
+
+    /// Returns the key of the service in [`Services`] as it appears in the registry, e.g. `tokenApi`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Service::Firehose => "firehose",
+            Service::Sps => "sps",
+            Service::Subgraphs => "subgraphs",
+            Service::Substreams => "substreams",
+            Service::TokenApi => "tokenApi",
+        }
+    }
+}
+
+impl std::fmt::Display for Service {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Services {
+    /// Returns the URLs of `service`, empty if the network doesn't provide it
+    pub fn urls(&self, service: Service) -> &[String] {
+        let urls = match service {
+            Service::Firehose => &self.firehose,
+            Service::Sps => &self.sps,
+            Service::Subgraphs => &self.subgraphs,
+            Service::Substreams => &self.substreams,
+            Service::TokenApi => &self.token_api,
+        };
+        urls.as_deref().unwrap_or_default()
+    }
+}
+
+/// Filters the networks of a registry
+///
+/// Created by [`NetworksRegistry::query`]. Every filter that is set has to match. Filters that
+/// take a value can be repeated: network types and protocols match any of the given values,
+/// services, Token API features and relation kinds have to be present all together.
+///
+/// # Example
+///
+/// ```
+/// use graph_networks_registry::{NetworkType, NetworksRegistry, Protocol, Service};
+///
+/// # fn example(registry: &NetworksRegistry) {
+/// let ethereum_firehose: Vec<_> = registry
+///     .query()
+///     .network_type(NetworkType::Mainnet)
+///     .issuance_rewards(true)
+///     .service(Service::Firehose)
+///     .protocol(Protocol::Ethereum)
+///     .into_iter()
+///     .collect();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct NetworkQuery<'a> {
+    networks: &'a [Network],
+    network_types: Vec<NetworkType>,
+    issuance_rewards: Option<bool>,
+    protocols: Vec<Protocol>,
+    services: Vec<Service>,
+    token_api_features: Vec<Feature>,
+    relation_kinds: Vec<RelationKind>,
+    deprecated_in_firehose: Option<bool>,
+    deprecated_in_graph_node: Option<bool>,
+    deprecated_in_token_api: Option<bool>,
+    #[cfg(feature = "chrono")]
+    as_of: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Iterator over the networks matching a [`NetworkQuery`]
+#[derive(Debug, Clone)]
+pub struct NetworkQueryIter<'a> {
+    query: NetworkQuery<'a>,
+    networks: slice::Iter<'a, Network>,
+}
+
+impl NetworksRegistry {
+    /// Starts a query over the networks of the registry, matching all networks until filters are added
+    pub fn query(&self) -> NetworkQuery<'_> {
+        NetworkQuery {
+            networks: &self.networks,
+            network_types: Vec::new(),
+            issuance_rewards: None,
+            protocols: Vec::new(),
+            services: Vec::new(),
+            token_api_features: Vec::new(),
+            relation_kinds: Vec::new(),
+            deprecated_in_firehose: None,
+            deprecated_in_graph_node: None,
+            deprecated_in_token_api: None,
+            #[cfg(feature = "chrono")]
+            as_of: None,
+        }
+    }
+}
+
+impl<'a> NetworkQuery<'a> {
+    /// Matches networks of this type, or any of the types if repeated
+    pub fn network_type(mut self, network_type: NetworkType) -> Self {
+        self.network_types.push(network_type);
+        self
+    }
+
+    /// Matches networks with or without issuance rewards on The Graph Network
+    pub fn issuance_rewards(mut self, issuance_rewards: bool) -> Self {
+        self.issuance_rewards = Some(issuance_rewards);
+        self
+    }
+
+    /// Matches networks with this graph-node protocol, or any of the protocols if repeated
+    pub fn protocol(mut self, protocol: Protocol) -> Self {
+        self.protocols.push(protocol);
+        self
+    }
+
+    /// Matches networks that list at least one URL for `service`
+    pub fn service(mut self, service: Service) -> Self {
+        self.services.push(service);
+        self
+    }
+
+    /// Matches networks whose Token API supports `feature`
+    pub fn token_api_feature(mut self, feature: Feature) -> Self {
+        self.token_api_features.push(feature);
+        self
+    }
+
+    /// Matches networks that have a relation of this kind to another network
+    pub fn relation_kind(mut self, kind: RelationKind) -> Self {
+        self.relation_kinds.push(kind);
+        self
+    }
+
+    /// Matches networks that are, or aren't, deprecated in Firehose software
+    pub fn deprecated_in_firehose(mut self, deprecated: bool) -> Self {
+        self.deprecated_in_firehose = Some(deprecated);
+        self
+    }
+
+    /// Matches networks that are, or aren't, deprecated in Graph Node software
+    pub fn deprecated_in_graph_node(mut self, deprecated: bool) -> Self {
+        self.deprecated_in_graph_node = Some(deprecated);
+        self
+    }
+
+    /// Matches networks that are, or aren't, deprecated in Token API software
+    pub fn deprecated_in_token_api(mut self, deprecated: bool) -> Self {
+        self.deprecated_in_token_api = Some(deprecated);
+        self
+    }
+
+    /// Evaluates the deprecation filters at `now`, ignoring deprecations scheduled after it
+    ///
+    /// Without this, a network counts as deprecated as soon as it has a `deprecatedAt` date. With it,
+    /// a `deprecatedAt` that isn't an RFC 3339 date-time doesn't count.
+    #[cfg(feature = "chrono")]
+    pub fn as_of(mut self, now: chrono::DateTime<chrono::Utc>) -> Self {
+        self.as_of = Some(now);
+        self
+    }
+
+    /// Returns `true` if `network` matches all filters of the query
+    pub fn matches(&self, network: &Network) -> bool {
+        let protocol = network.graph_node.as_ref().and_then(|graph_node| graph_node.protocol.as_ref());
+        let features = network
+            .token_api
+            .as_ref()
+            .and_then(|token_api| token_api.features.as_deref())
+            .unwrap_or_default();
+        let relations = network.relations.as_deref().unwrap_or_default();
+
+        (self.network_types.is_empty() || self.network_types.contains(&network.network_type))
+            && self
+                .issuance_rewards
+                .map_or(true, |issuance_rewards| network.issuance_rewards == issuance_rewards)
+            && (self.protocols.is_empty() || protocol.is_some_and(|protocol| self.protocols.contains(protocol)))
+            && self.services.iter().all(|&service| !network.services.urls(service).is_empty())
+            && self.token_api_features.iter().all(|feature| features.contains(feature))
+            && self
+                .relation_kinds
+                .iter()
+                .all(|kind| relations.iter().any(|relation| relation.kind == *kind))
+            && self.deprecation_matches(
+                self.deprecated_in_firehose,
+                network.firehose.as_ref().and_then(|firehose| firehose.deprecated_at.as_deref()),
+            )
+            && self.deprecation_matches(
+                self.deprecated_in_graph_node,
+                network
+                    .graph_node
+                    .as_ref()
+                    .and_then(|graph_node| graph_node.deprecated_at.as_deref()),
+            )
+            && self.deprecation_matches(
+                self.deprecated_in_token_api,
+                network.token_api.as_ref().and_then(|token_api| token_api.deprecated_at.as_deref()),
+            )
+    }
+
+    /// Returns the matching networks, in the order of the registry
+    pub fn iter(&self) -> NetworkQueryIter<'a> {
+        self.clone().into_iter()
+    }
+
+    fn deprecation_matches(&self, filter: Option<bool>, deprecated_at: Option<&str>) -> bool {
+        #[cfg(feature = "chrono")]
+        let deprecated_at = match self.as_of {
+            Some(now) => deprecated_at.filter(|deprecated_at| timestamp::is_reached(timestamp::parse(deprecated_at), now)),
+            None => deprecated_at,
+        };
+        filter.map_or(true, |deprecated| deprecated_at.is_some() == deprecated)
+    }
+}
+
+impl<'a> IntoIterator for NetworkQuery<'a> {
+    type Item = &'a Network;
+    type IntoIter = NetworkQueryIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        NetworkQueryIter {
+            networks: self.networks.iter(),
+            query: self,
+        }
+    }
+}
+
+impl<'a> Iterator for NetworkQueryIter<'a> {
+    type Item = &'a Network;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let query = &self.query;
+        self.networks.find(|network| query.matches(network))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUERY_REGISTRY_JSON: &str = r#"{
+        "$schema": "https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_vx_x.json",
+        "version": "x.x.x",
+        "title": "Test Registry",
+        "description": "Test Registry",
+        "updatedAt": "2025-01-01T00:00:00Z",
+        "networks": [
+            {
+                "id": "mainnet",
+                "fullName": "Ethereum Mainnet",
+                "shortName": "Ethereum",
+                "caip2Id": "eip155:1",
+                "networkType": "mainnet",
+                "issuanceRewards": true,
+                "graphNode": { "protocol": "ethereum" },
+                "services": { "firehose": ["eth.firehose.example:443"], "tokenApi": ["https://token-api.example"] },
+                "tokenApi": { "features": ["tokens", "nfts", "dexes"] }
+            },
+            {
+                "id": "sepolia",
+                "fullName": "Ethereum Sepolia Testnet",
+                "shortName": "Sepolia",
+                "caip2Id": "eip155:11155111",
+                "networkType": "testnet",
+                "issuanceRewards": true,
+                "graphNode": { "protocol": "ethereum" },
+                "services": { "firehose": ["sepolia.firehose.example:443"] },
+                "relations": [{ "kind": "testnetOf", "network": "mainnet" }]
+            },
+            {
+                "id": "near-mainnet",
+                "fullName": "NEAR Mainnet",
+                "shortName": "NEAR",
+                "caip2Id": "near:mainnet",
+                "networkType": "mainnet",
+                "issuanceRewards": true,
+                "graphNode": { "protocol": "near" },
+                "services": { "firehose": ["near.firehose.example:443"] }
+            },
+            {
+                "id": "matic",
+                "fullName": "Polygon Mainnet",
+                "shortName": "Polygon",
+                "caip2Id": "eip155:137",
+                "networkType": "mainnet",
+                "issuanceRewards": false,
+                "graphNode": { "protocol": "ethereum" },
+                "services": { "subgraphs": ["https://api.studio.example/deploy"] },
+                "tokenApi": { "features": ["tokens", "nfts"] }
+            },
+            {
+                "id": "goerli",
+                "fullName": "Ethereum Goerli Testnet",
+                "shortName": "Goerli",
+                "caip2Id": "eip155:5",
+                "networkType": "testnet",
+                "issuanceRewards": false,
+                "graphNode": { "protocol": "ethereum", "deprecatedAt": "2025-03-01T00:00:00Z" },
+                "services": { "firehose": ["goerli.firehose.example:443"] },
+                "firehose": {
+                    "blockType": "sf.ethereum.type.v2.Block",
+                    "bufUrl": "https://buf.build/streamingfast/firehose-ethereum",
+                    "bytesEncoding": "hex",
+                    "deprecatedAt": "2025-05-01T00:00:00Z"
+                },
+                "relations": [{ "kind": "testnetOf", "network": "mainnet" }]
+            },
+            {
+                "id": "mumbai",
+                "fullName": "Polygon Mumbai Testnet",
+                "shortName": "Mumbai",
+                "caip2Id": "eip155:80001",
+                "networkType": "testnet",
+                "issuanceRewards": false,
+                "graphNode": { "protocol": "ethereum", "deprecatedAt": "2025-05-15T00:00:00Z" },
+                "services": {},
+                "relations": [{ "kind": "testnetOf", "network": "matic" }]
+            }
+        ]
+    }"#;
+
+    fn ids<'a>(networks: impl IntoIterator<Item = &'a Network>) -> Vec<&'a str> {
+        networks.into_iter().map(|network| network.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_query() {
+        let registry = NetworksRegistry::from_json(QUERY_REGISTRY_JSON).unwrap();
+
+        assert_eq!(registry.query().iter().count(), registry.networks.len());
+
+        let rewarded_mainnets = ids(registry.query().network_type(NetworkType::Mainnet).issuance_rewards(true));
+        assert_eq!(rewarded_mainnets, ["mainnet", "near-mainnet"]);
+
+        let query = registry.query().service(Service::Firehose).protocol(Protocol::Ethereum);
+        assert_eq!(ids(query.iter()), ["mainnet", "sepolia", "goerli"]);
+        assert!(query.matches(&registry.networks[0]));
+        assert!(!query.matches(&registry.networks[2]));
+        let near_or_ethereum = registry.query().protocol(Protocol::Near).protocol(Protocol::Ethereum);
+        assert_eq!(near_or_ethereum.iter().count(), registry.networks.len());
+        assert_eq!(
+            ids(registry.query().service(Service::TokenApi).service(Service::Firehose)),
+            ["mainnet"]
+        );
+
+        let nfts = ids(registry.query().token_api_feature(Feature::Nfts).token_api_feature(Feature::Dexes));
+        assert_eq!(nfts, ["mainnet"]);
+        assert_eq!(ids(registry.query().token_api_feature(Feature::Nfts)), ["mainnet", "matic"]);
+        assert!(ids(registry.query().token_api_feature(Feature::Unknown("swaps".to_string()))).is_empty());
+
+        let testnets = ids(registry.query().relation_kind(RelationKind::TestnetOf));
+        assert_eq!(testnets, ["sepolia", "goerli", "mumbai"]);
+        assert!(ids(registry
+            .query()
+            .relation_kind(RelationKind::TestnetOf)
+            .network_type(NetworkType::Mainnet))
+        .is_empty());
+        let mainnets_and_testnets = registry
+            .query()
+            .network_type(NetworkType::Mainnet)
+            .network_type(NetworkType::Testnet);
+        assert_eq!(mainnets_and_testnets.iter().count(), registry.networks.len());
+
+        assert_eq!(ids(registry.query().deprecated_in_graph_node(true)), ["goerli", "mumbai"]);
+        assert_eq!(
+            ids(registry.query().deprecated_in_graph_node(false)),
+            ["mainnet", "sepolia", "near-mainnet", "matic"]
+        );
+        assert_eq!(ids(registry.query().deprecated_in_firehose(true)), ["goerli"]);
+        assert!(ids(registry.query().deprecated_in_token_api(true)).is_empty());
+    }
+
+    #[test]
+    fn test_services() {
+        // every field of `Services` is a `Service`, so a service added by the schema fails here
+        let urls = Some(vec!["service.example:443".to_string()]);
+        let services = Services {
+            firehose: urls.clone(),
+            sps: urls.clone(),
+            subgraphs: urls.clone(),
+            substreams: urls.clone(),
+            token_api: urls,
+            extra: Default::default(),
+        };
+        let value = serde_json::to_value(&services).unwrap();
+        let mut keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        let mut names: Vec<&str> = Service::ALL.iter().map(Service::as_str).collect();
+        names.sort_unstable();
+        assert_eq!(keys, names);
+        assert!(Service::ALL.iter().all(|&service| services.urls(service).len() == 1));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_query_as_of() {
+        use chrono::{TimeZone, Utc};
+
+        let registry = NetworksRegistry::from_json(QUERY_REGISTRY_JSON).unwrap();
+        let deprecated_at = |year, month, day| {
+            ids(registry
+                .query()
+                .deprecated_in_graph_node(true)
+                .as_of(Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()))
+        };
+
+        assert!(deprecated_at(2025, 1, 1).is_empty());
+        assert_eq!(deprecated_at(2025, 3, 1), ["goerli"]);
+        assert_eq!(deprecated_at(2025, 6, 1), ["goerli", "mumbai"]);
+
+        let firehose_active = Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            registry.query().deprecated_in_firehose(false).as_of(firehose_active).iter().count(),
+            registry.networks.len()
+        );
+    }
+}
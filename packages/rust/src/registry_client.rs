@@ -0,0 +1,1201 @@
+use std::path::PathBuf;
+#[cfg(feature = "blocking")]
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use reqwest::StatusCode;
+
+use crate::cache::{CacheEntry, RegistryCache, Validators};
+use crate::error::Error;
+use crate::types::NetworksRegistry;
+use crate::version::*;
+
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Client for fetching the networks registry from configurable sources
+///
+/// Sources are tried in order: the primary base URL, the fallback base URL, then any extra mirrors.
+/// The first source that returns a valid registry wins.
+///
+/// # Example
+///
+/// ```no_run
+/// use graph_networks_registry::{RegistryClient, RegistryVersion};
+/// use std::time::Duration;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = RegistryClient::builder()
+///     .mirror("https://registry-mirror.example.com")
+///     .timeout(Duration::from_secs(5))
+///     .header("x-api-key", "secret")
+///     .build()?;
+///
+/// let registry = client.from_version(RegistryVersion::Latest).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RegistryClient {
+    base_urls: Vec<String>,
+    cache: Option<RegistryCache>,
+    serve_stale: bool,
+    strict: bool,
+    #[cfg(feature = "blocking")]
+    timeout: Option<Duration>,
+    #[cfg(feature = "blocking")]
+    headers: HeaderMap,
+    #[cfg(feature = "fetch")]
+    http: reqwest::Client,
+    /// Created on first use: a blocking client must not be created or dropped inside an async runtime
+    #[cfg(feature = "blocking")]
+    blocking_http: Arc<OnceLock<reqwest::blocking::Client>>,
+}
+
+impl Default for RegistryClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegistryClient {
+    /// Creates a client with the default registry sources
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, like `reqwest::Client::new`
+    pub fn new() -> Self {
+        RegistryClientBuilder::default()
+            .build()
+            .expect("Failed to build the default registry client")
+    }
+
+    /// Returns a builder to configure sources, timeout and request headers
+    pub fn builder() -> RegistryClientBuilder {
+        RegistryClientBuilder::default()
+    }
+
+    /// Returns the URLs that are tried for `version`, in order
+    pub fn urls(&self, version: RegistryVersion<'_>) -> Vec<String> {
+        self.base_urls.iter().map(|base_url| version.get_url_with_base(base_url)).collect()
+    }
+
+    /// Fetches a registry version, trying each configured source in order
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidVersion` without any request if an `Exact` version is not a valid version number,
+    /// or `Error::AllSourcesFailed` with the error of every source if none returns a valid registry
+    #[cfg(feature = "fetch")]
+    pub async fn from_version(&self, version: RegistryVersion<'_>) -> Result<NetworksRegistry, Error> {
+        self.fetch(version).await.map(|fetched| fetched.registry)
+    }
+
+    /// Fetches a registry version, trying each configured source in order and blocking the
+    /// current thread until done
+    ///
+    /// Does not require an async runtime, but must not be called from within one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidVersion` without any request if an `Exact` version is not a valid version number,
+    /// or `Error::AllSourcesFailed` with the error of every source if none returns a valid registry
+    #[cfg(feature = "blocking")]
+    pub fn from_version_blocking(&self, version: RegistryVersion<'_>) -> Result<NetworksRegistry, Error> {
+        self.fetch_blocking(version).map(|fetched| fetched.registry)
+    }
+
+    /// Fetches the registry from `url` instead of the configured sources
+    ///
+    /// Sends the configured headers and uses the timeout, cache and strictness of the client.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::HttpStatus` if the response is not successful, or an error if the request fails
+    /// or the response doesn't contain a valid registry
+    #[cfg(feature = "fetch")]
+    pub async fn from_url(&self, url: &str) -> Result<NetworksRegistry, Error> {
+        debug!(url, "fetching registry");
+        self.fetch_url(url).await.map(|fetched| fetched.registry)
+    }
+
+    /// Fetches the registry from `url` instead of the configured sources, blocking the current
+    /// thread until done
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::HttpStatus` if the response is not successful, or an error if the request fails
+    /// or the response doesn't contain a valid registry
+    #[cfg(feature = "blocking")]
+    pub fn from_url_blocking(&self, url: &str) -> Result<NetworksRegistry, Error> {
+        debug!(url, "fetching registry");
+        self.fetch_url_blocking(url).map(|fetched| fetched.registry)
+    }
+
+    /// Like [`RegistryClient::from_version`], but also reports which URL the registry came from
+    /// and whether it was served from the cache
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidVersion` without any request if an `Exact` version is not a valid version number,
+    /// or `Error::AllSourcesFailed` with the error of every source if none returns a valid registry
+    /// and no stale copy can be served
+    #[cfg(feature = "fetch")]
+    pub async fn fetch(&self, version: RegistryVersion<'_>) -> Result<FetchedRegistry, Error> {
+        version.number()?;
+        let urls = self.urls(version);
+        let mut attempts = Vec::with_capacity(urls.len());
+        for url in urls {
+            debug!(url = %url, "fetching registry");
+            match self.fetch_url(&url).await {
+                Ok(fetched) => return Ok(fetched),
+                Err(err) => {
+                    warn!(url = %url, error = %err, "failed to fetch registry, trying next source");
+                    attempts.push((url, err));
+                }
+            }
+        }
+        self.load_stale(&attempts).ok_or(Error::AllSourcesFailed { attempts })
+    }
+
+    /// Like [`RegistryClient::from_version_blocking`], but also reports which URL the registry came from
+    /// and whether it was served from the cache
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidVersion` without any request if an `Exact` version is not a valid version number,
+    /// or `Error::AllSourcesFailed` with the error of every source if none returns a valid registry
+    /// and no stale copy can be served
+    #[cfg(feature = "blocking")]
+    pub fn fetch_blocking(&self, version: RegistryVersion<'_>) -> Result<FetchedRegistry, Error> {
+        version.number()?;
+        let urls = self.urls(version);
+        let mut attempts = Vec::with_capacity(urls.len());
+        for url in urls {
+            debug!(url = %url, "fetching registry");
+            match self.fetch_url_blocking(&url) {
+                Ok(fetched) => return Ok(fetched),
+                Err(err) => {
+                    warn!(url = %url, error = %err, "failed to fetch registry, trying next source");
+                    attempts.push((url, err));
+                }
+            }
+        }
+        self.load_stale(&attempts).ok_or(Error::AllSourcesFailed { attempts })
+    }
+
+    /// Lists the published registry versions, trying each configured source in order
+    ///
+    /// Versions are read from the directory listing of each base URL. The GitHub fallback has no
+    /// listing of its own and is listed through the GitHub API instead.
+    ///
+    /// # Returns
+    ///
+    /// The versions listed by the first source that answers, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AllSourcesFailed` with the error of every source if none lists any version
+    #[cfg(feature = "fetch")]
+    pub async fn list_versions(&self) -> Result<Vec<RegistryVersionNumber>, Error> {
+        let urls = self.listing_urls();
+        let mut attempts = Vec::with_capacity(urls.len());
+        for url in urls {
+            debug!(url = %url, "listing registry versions");
+            match self.get_text(&url).await.and_then(|listing| listed_versions(&url, &listing)) {
+                Ok(versions) => return Ok(versions),
+                Err(err) => {
+                    warn!(url = %url, error = %err, "failed to list registry versions, trying next source");
+                    attempts.push((url, err));
+                }
+            }
+        }
+        Err(Error::AllSourcesFailed { attempts })
+    }
+
+    /// Like [`RegistryClient::list_versions`], blocking the current thread until done
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AllSourcesFailed` with the error of every source if none lists any version
+    #[cfg(feature = "blocking")]
+    pub fn list_versions_blocking(&self) -> Result<Vec<RegistryVersionNumber>, Error> {
+        let urls = self.listing_urls();
+        let mut attempts = Vec::with_capacity(urls.len());
+        for url in urls {
+            debug!(url = %url, "listing registry versions");
+            match self.get_text_blocking(&url).and_then(|listing| listed_versions(&url, &listing)) {
+                Ok(versions) => return Ok(versions),
+                Err(err) => {
+                    warn!(url = %url, error = %err, "failed to list registry versions, trying next source");
+                    attempts.push((url, err));
+                }
+            }
+        }
+        Err(Error::AllSourcesFailed { attempts })
+    }
+
+    /// Returns the newest published patch of `major`.`minor`, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AllSourcesFailed` if the published versions cannot be listed
+    #[cfg(feature = "fetch")]
+    pub async fn latest_patch(&self, major: u32, minor: u32) -> Result<Option<RegistryVersionNumber>, Error> {
+        Ok(RegistryVersionNumber::latest_patch(&self.list_versions().await?, major, minor))
+    }
+
+    /// Like [`RegistryClient::latest_patch`], blocking the current thread until done
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AllSourcesFailed` if the published versions cannot be listed
+    #[cfg(feature = "blocking")]
+    pub fn latest_patch_blocking(&self, major: u32, minor: u32) -> Result<Option<RegistryVersionNumber>, Error> {
+        Ok(RegistryVersionNumber::latest_patch(&self.list_versions_blocking()?, major, minor))
+    }
+
+    /// Returns the URLs listing the files of each source, in order
+    fn listing_urls(&self) -> Vec<String> {
+        self.base_urls
+            .iter()
+            .map(|base_url| match base_url.as_str() {
+                DEFAULT_FALLBACK_BASE_URL => DEFAULT_FALLBACK_LISTING_URL.to_string(),
+                base_url => format!("{}/", base_url.trim_end_matches('/')),
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "fetch")]
+    async fn get_text(&self, url: &str) -> Result<String, Error> {
+        let response = self.http.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(Error::HttpStatus {
+                url: url.to_string(),
+                status: response.status().as_u16(),
+            });
+        }
+        Ok(response.text().await?)
+    }
+
+    #[cfg(feature = "blocking")]
+    fn get_text_blocking(&self, url: &str) -> Result<String, Error> {
+        let response = self.blocking_http()?.get(url).send()?;
+        if !response.status().is_success() {
+            return Err(Error::HttpStatus {
+                url: url.to_string(),
+                status: response.status().as_u16(),
+            });
+        }
+        Ok(response.text()?)
+    }
+
+    #[cfg(feature = "fetch")]
+    async fn fetch_url(&self, url: &str) -> Result<FetchedRegistry, Error> {
+        let cached = self.load_cached(url);
+        let conditional_headers = cached.as_ref().map(|entry| entry.validators.to_conditional_headers());
+        let response = self.http.get(url).headers(conditional_headers.unwrap_or_default()).send().await?;
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (response.status(), cached) {
+            debug!(url, "cached registry not modified");
+            return self.parsed(url, &cached.body, CacheStatus::NotModified);
+        }
+        if !response.status().is_success() {
+            return Err(Error::HttpStatus {
+                url: url.to_string(),
+                status: response.status().as_u16(),
+            });
+        }
+        let validators = Validators::from_headers(response.headers());
+        let text = response.text().await?;
+        self.store(url, text, validators)
+    }
+
+    #[cfg(feature = "blocking")]
+    fn fetch_url_blocking(&self, url: &str) -> Result<FetchedRegistry, Error> {
+        let cached = self.load_cached(url);
+        let conditional_headers = cached.as_ref().map(|entry| entry.validators.to_conditional_headers());
+        let response = self
+            .blocking_http()?
+            .get(url)
+            .headers(conditional_headers.unwrap_or_default())
+            .send()?;
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (response.status(), cached) {
+            debug!(url, "cached registry not modified");
+            return self.parsed(url, &cached.body, CacheStatus::NotModified);
+        }
+        if !response.status().is_success() {
+            return Err(Error::HttpStatus {
+                url: url.to_string(),
+                status: response.status().as_u16(),
+            });
+        }
+        let validators = Validators::from_headers(response.headers());
+        let text = response.text()?;
+        self.store(url, text, validators)
+    }
+
+    fn load_cached(&self, url: &str) -> Option<CacheEntry> {
+        self.cache.as_ref().and_then(|cache| cache.load(url))
+    }
+
+    /// Parses a freshly downloaded document and caches it if it is a valid registry
+    fn store(&self, url: &str, text: String, validators: Validators) -> Result<FetchedRegistry, Error> {
+        let fetched = self.parsed(url, &text, CacheStatus::Fetched)?;
+        if let Some(cache) = &self.cache {
+            // a cache that can't be written only costs a full download next time
+            if let Err(_err) = cache.store(url, &text, validators) {
+                warn!(url, error = %_err, "failed to write registry cache");
+            }
+        }
+        Ok(fetched)
+    }
+
+    /// Returns the first cached copy for the failed attempts that still parses, if stale copies may be served
+    fn load_stale(&self, attempts: &[(String, Error)]) -> Option<FetchedRegistry> {
+        if !self.serve_stale {
+            return None;
+        }
+        let stale = attempts
+            .iter()
+            .find_map(|(url, _)| self.parsed(url, &self.load_cached(url)?.body, CacheStatus::Stale).ok());
+        if let Some(_stale) = &stale {
+            warn!(url = %_stale.url, "all registry sources failed, serving stale cached copy");
+        }
+        stale
+    }
+
+    fn parsed(&self, url: &str, text: &str, cache_status: CacheStatus) -> Result<FetchedRegistry, Error> {
+        let registry = NetworksRegistry::from_json(text).map_err(|err| err.at(url))?;
+        if self.strict {
+            registry.ensure_compatible()?;
+        }
+        Ok(FetchedRegistry {
+            registry,
+            url: url.to_string(),
+            cache_status,
+        })
+    }
+
+    #[cfg(feature = "blocking")]
+    fn blocking_http(&self) -> Result<&reqwest::blocking::Client, Error> {
+        if let Some(http) = self.blocking_http.get() {
+            return Ok(http);
+        }
+        let mut builder = reqwest::blocking::Client::builder().default_headers(self.headers.clone());
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let http = builder.build()?;
+        Ok(self.blocking_http.get_or_init(|| http))
+    }
+}
+
+fn listed_versions(url: &str, listing: &str) -> Result<Vec<RegistryVersionNumber>, Error> {
+    let versions = RegistryVersionNumber::find_in_listing(listing);
+    if versions.is_empty() {
+        return Err(Error::NoVersionsListed { location: url.to_string() });
+    }
+    Ok(versions)
+}
+
+/// A registry returned by [`RegistryClient::fetch`], with where it came from
+#[derive(Debug, Clone)]
+pub struct FetchedRegistry {
+    pub registry: NetworksRegistry,
+    /// URL of the source that served the registry
+    pub url: String,
+    pub cache_status: CacheStatus,
+}
+
+impl FetchedRegistry {
+    /// Returns true if every source failed and the registry is an older cached copy
+    pub fn is_stale(&self) -> bool {
+        self.cache_status == CacheStatus::Stale
+    }
+}
+
+/// How a fetched registry relates to the on-disk cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// Downloaded from the source
+    Fetched,
+    /// The source confirmed the cached copy is up to date
+    NotModified,
+    /// Every source failed, the cached copy may be out of date
+    Stale,
+}
+
+/// Builder for [`RegistryClient`]
+#[derive(Debug, Clone)]
+pub struct RegistryClientBuilder {
+    primary_base_url: String,
+    fallback_base_url: Option<String>,
+    mirrors: Vec<String>,
+    timeout: Option<Duration>,
+    user_agent: String,
+    headers: Vec<(String, String)>,
+    cache_dir: Option<PathBuf>,
+    serve_stale: bool,
+    strict: bool,
+}
+
+impl Default for RegistryClientBuilder {
+    fn default() -> Self {
+        RegistryClientBuilder {
+            primary_base_url: DEFAULT_PRIMARY_BASE_URL.to_string(),
+            fallback_base_url: Some(DEFAULT_FALLBACK_BASE_URL.to_string()),
+            mirrors: Vec::new(),
+            timeout: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            headers: Vec::new(),
+            cache_dir: None,
+            serve_stale: false,
+            strict: false,
+        }
+    }
+}
+
+impl RegistryClientBuilder {
+    /// Sets the base URL tried first (default: https://networks-registry.thegraph.com)
+    pub fn primary_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.primary_base_url = base_url.into();
+        self
+    }
+
+    /// Sets the base URL tried when the primary fails (default: the networks-registry GitHub repository)
+    pub fn fallback_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.fallback_base_url = Some(base_url.into());
+        self
+    }
+
+    /// Removes the fallback base URL, so only the primary and mirrors are tried
+    pub fn no_fallback(mut self) -> Self {
+        self.fallback_base_url = None;
+        self
+    }
+
+    /// Adds a mirror base URL, tried after the primary and fallback in the order added
+    pub fn mirror(mut self, base_url: impl Into<String>) -> Self {
+        self.mirrors.push(base_url.into());
+        self
+    }
+
+    /// Sets a timeout for each request, from connecting until the response body is read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header (default: graph-networks-registry/{crate version})
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Adds a header sent with every request
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Caches the last good registry for each URL in `dir`
+    ///
+    /// Later fetches revalidate the cached copy with `If-None-Match` / `If-Modified-Since`
+    /// and reuse it when the source answers 304 Not Modified.
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Serves the cached copy, flagged as [`CacheStatus::Stale`], when every source fails (default: false)
+    ///
+    /// Has no effect without a [`RegistryClientBuilder::cache_dir`].
+    pub fn serve_stale(mut self, serve_stale: bool) -> Self {
+        self.serve_stale = serve_stale;
+        self
+    }
+
+    /// Rejects registries whose schema version is not the one this crate supports (default: false)
+    ///
+    /// A source that serves an incompatible registry counts as failed with `Error::SchemaVersionMismatch`,
+    /// and the next source is tried.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Builds the client
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a header name or value is invalid, or the HTTP client cannot be created
+    pub fn build(self) -> Result<RegistryClient, Error> {
+        let mut headers = HeaderMap::new();
+        let user_agent = HeaderValue::from_str(&self.user_agent)
+            .map_err(|_| Error::InvalidConfig(format!("invalid user agent: {:?}", self.user_agent)))?;
+        headers.insert(USER_AGENT, user_agent);
+        for (name, value) in &self.headers {
+            let header_name =
+                HeaderName::from_bytes(name.as_bytes()).map_err(|_| Error::InvalidConfig(format!("invalid header name: {:?}", name)))?;
+            let header_value =
+                HeaderValue::from_str(value).map_err(|_| Error::InvalidConfig(format!("invalid value for header {}", name)))?;
+            headers.append(header_name, header_value);
+        }
+
+        let base_urls = std::iter::once(self.primary_base_url)
+            .chain(self.fallback_base_url)
+            .chain(self.mirrors)
+            .collect();
+
+        #[cfg(feature = "fetch")]
+        let http = {
+            let mut builder = reqwest::Client::builder().default_headers(headers.clone());
+            if let Some(timeout) = self.timeout {
+                builder = builder.timeout(timeout);
+            }
+            builder.build()?
+        };
+
+        Ok(RegistryClient {
+            base_urls,
+            cache: self.cache_dir.map(RegistryCache::new),
+            serve_stale: self.serve_stale,
+            strict: self.strict,
+            #[cfg(feature = "blocking")]
+            timeout: self.timeout,
+            #[cfg(feature = "blocking")]
+            headers,
+            #[cfg(feature = "fetch")]
+            http,
+            #[cfg(feature = "blocking")]
+            blocking_http: Arc::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::REGISTRY_JSON;
+
+    fn test_client(primary_base_url: &str, fallback_base_url: &str) -> RegistryClient {
+        RegistryClient::builder()
+            .primary_base_url(primary_base_url)
+            .fallback_base_url(fallback_base_url)
+            .build()
+            .unwrap()
+    }
+
+    /// Returns the errors of each attempted source from an `Error::AllSourcesFailed`
+    fn attempt_errors<T: std::fmt::Debug>(result: Result<T, Error>) -> Vec<Error> {
+        match result {
+            Err(Error::AllSourcesFailed { attempts }) => attempts.into_iter().map(|(_, err)| err).collect(),
+            other => panic!("expected AllSourcesFailed, got {:?}", other),
+        }
+    }
+
+    /// Returns an empty cache directory unique to `name`
+    fn test_cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("graph-networks-registry-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[cfg(feature = "fetch")]
+    mod fetch_tests {
+        use super::*;
+        use mockito::Server;
+
+        #[tokio::test]
+        async fn test_list_versions() {
+            let mut primary_server = Server::new_async().await;
+            let mut fallback_server = Server::new_async().await;
+            let client = test_client(&primary_server.url(), &format!("{}/public", fallback_server.url()));
+
+            let listing = r#"<html><body>
+                <a href="TheGraphNetworksRegistry_v0_7_1.json">TheGraphNetworksRegistry_v0_7_1.json</a>
+                <a href="TheGraphNetworksRegistry_v0_6_3.json">TheGraphNetworksRegistry_v0_6_3.json</a>
+                <a href="TheGraphNetworksRegistry_v0_7_x.json">TheGraphNetworksRegistry_v0_7_x.json</a>
+                <a href="TheGraphNetworksRegistry_v0_6_12.json">TheGraphNetworksRegistry_v0_6_12.json</a>
+            </body></html>"#;
+            let primary_mock = primary_server
+                .mock("GET", "/")
+                .with_status(200)
+                .with_body(listing)
+                .create_async()
+                .await;
+            let versions = client.list_versions().await.unwrap();
+            assert_eq!(
+                versions,
+                vec![
+                    RegistryVersionNumber::new(0, 6, 3),
+                    RegistryVersionNumber::new(0, 6, 12),
+                    RegistryVersionNumber::new(0, 7, 1),
+                ]
+            );
+            assert_eq!(client.latest_patch(0, 6).await.unwrap(), Some(RegistryVersionNumber::new(0, 6, 12)));
+            assert_eq!(client.latest_patch(0, 5).await.unwrap(), None);
+            primary_mock.remove_async().await;
+
+            // A page without versions counts as a failed source
+            primary_server
+                .mock("GET", "/")
+                .with_status(200)
+                .with_body("<html>Welcome</html>")
+                .create_async()
+                .await;
+            fallback_server
+                .mock("GET", "/public/")
+                .with_status(200)
+                .with_body(r#"[{"name": "TheGraphNetworksRegistry_v0_5_0.json", "type": "file"}]"#)
+                .create_async()
+                .await;
+            assert_eq!(client.list_versions().await.unwrap(), vec![RegistryVersionNumber::new(0, 5, 0)]);
+
+            let client = RegistryClient::builder()
+                .primary_base_url(primary_server.url())
+                .no_fallback()
+                .build()
+                .unwrap();
+            let errors = attempt_errors(client.list_versions().await);
+            assert!(matches!(&errors[..], [Error::NoVersionsListed { location }] if *location == format!("{}/", primary_server.url())));
+
+            // The GitHub fallback is listed through the GitHub API
+            assert_eq!(RegistryClient::new().listing_urls()[1], DEFAULT_FALLBACK_LISTING_URL);
+        }
+
+        #[tokio::test]
+        async fn test_version_numbers() {
+            let mut primary_server = Server::new_async().await;
+            let client = RegistryClient::builder()
+                .primary_base_url(primary_server.url())
+                .no_fallback()
+                .build()
+                .unwrap();
+
+            let primary_mock = primary_server
+                .mock("GET", "/TheGraphNetworksRegistry_v0_5_3.json")
+                .with_status(200)
+                .with_body(REGISTRY_JSON)
+                .expect(2)
+                .create_async()
+                .await;
+            client.from_version(RegistryVersion::Exact("0.5.3")).await.unwrap();
+            client.from_version(RegistryVersionNumber::new(0, 5, 3).into()).await.unwrap();
+            primary_mock.assert();
+
+            // Typos fail before any request is made
+            let any_mock = primary_server.mock("GET", mockito::Matcher::Any).expect(0).create_async().await;
+            for version in ["0.5", "v0.5.x"] {
+                let result = client.from_version(RegistryVersion::Exact(version)).await;
+                assert!(matches!(result, Err(Error::InvalidVersion { value, .. }) if value == version));
+            }
+            any_mock.assert();
+        }
+
+        #[tokio::test]
+        async fn test_fallback_with_mock_server() {
+            let registry_path = format!("/TheGraphNetworksRegistry_v{}_x.json", SCHEMA_VERSION);
+
+            // Create two mock servers for primary and fallback URLs
+            let mut primary_server = Server::new_async().await;
+            let mut fallback_server = Server::new_async().await;
+
+            // Set up the mock servers
+            let client = test_client(&primary_server.url(), &fallback_server.url());
+
+            // Test Case 1: Primary succeeds
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .with_body(REGISTRY_JSON)
+                .create_async()
+                .await;
+
+            let fallback_mock = fallback_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .expect(0)
+                .with_body(REGISTRY_JSON)
+                .create_async()
+                .await;
+
+            let result = client.from_version(RegistryVersion::Latest).await;
+            assert!(result.is_ok(), "Should succeed with primary URL");
+            let registry = result.unwrap();
+            assert!(registry.get_network_by_graph_id("mainnet").is_some());
+            primary_mock.assert();
+            fallback_mock.assert();
+
+            // Test Case 2: Primary fails, fallback succeeds
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .with_status(500)
+                .create_async()
+                .await;
+
+            let fallback_mock = fallback_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .with_body(REGISTRY_JSON)
+                .create_async()
+                .await;
+
+            let result = client.from_version(RegistryVersion::Latest).await;
+            assert!(result.is_ok(), "Should succeed using fallback URL");
+            let registry = result.unwrap();
+            assert!(registry.get_network_by_graph_id("mainnet").is_some());
+            primary_mock.assert();
+            fallback_mock.assert();
+
+            // Test Case 3: Both primary and fallback fail
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .with_body("bye")
+                .create_async()
+                .await;
+
+            let fallback_mock = fallback_server
+                .mock("GET", registry_path.as_str())
+                .with_status(404)
+                .create_async()
+                .await;
+
+            let result = client.from_version(RegistryVersion::Latest).await;
+            let errors = attempt_errors(result);
+            assert!(matches!(errors[0], Error::ParseAt { .. }), "Should fail when both URLs fail");
+            assert!(matches!(errors[1], Error::HttpStatus { status: 404, .. }));
+            primary_mock.assert();
+            fallback_mock.assert();
+        }
+
+        #[tokio::test]
+        async fn test_http_errors() {
+            let mut primary_server = Server::new_async().await;
+            let mut fallback_server = Server::new_async().await;
+            let registry_path = format!("/TheGraphNetworksRegistry_v{}_x.json", SCHEMA_VERSION);
+
+            // Setup mock servers
+            let client = test_client(&primary_server.url(), &fallback_server.url());
+
+            // Test Case 1: Invalid JSON response
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .with_body("{invalid_json")
+                .create_async()
+                .await;
+
+            let result = client.from_version(RegistryVersion::Latest).await;
+            assert!(matches!(attempt_errors(result)[0], Error::ParseAt { .. }));
+            primary_mock.assert();
+
+            // Test Case 2: HTTP 404 on both servers
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .with_status(404)
+                .create_async()
+                .await;
+
+            let fallback_mock = fallback_server
+                .mock("GET", registry_path.as_str())
+                .with_status(404)
+                .create_async()
+                .await;
+
+            let result = client.from_version(RegistryVersion::Latest).await;
+            let Err(Error::AllSourcesFailed { attempts }) = result else {
+                panic!("Should fail with every attempt listed");
+            };
+            assert_eq!(attempts.len(), 2);
+            assert_eq!(attempts[0].0, format!("{}{}", primary_server.url(), registry_path));
+            assert_eq!(attempts[1].0, format!("{}{}", fallback_server.url(), registry_path));
+            for (url, err) in &attempts {
+                assert!(matches!(err, Error::HttpStatus { url: status_url, status: 404 } if status_url == url));
+            }
+            primary_mock.assert();
+            fallback_mock.assert();
+
+            // Test Case 3: Empty response
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .with_body("")
+                .create_async()
+                .await;
+
+            let result = client.from_version(RegistryVersion::Latest).await;
+            assert!(matches!(attempt_errors(result)[0], Error::ParseAt { .. }));
+            primary_mock.assert();
+
+            // Test Case 4: Valid JSON but invalid schema
+            let invalid_schema_json = r#"{
+                "$schema": "https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_vx_x.json",
+                "version": "x.x.x",
+                "title": "Test Registry",
+                "description": "Test Registry",
+                "updatedAt": "2025-01-01T00:00:00Z",
+                "networks": "not_an_array"
+            }"#;
+
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .with_body(invalid_schema_json)
+                .create_async()
+                .await;
+
+            let result = client.from_version(RegistryVersion::Latest).await;
+            assert!(matches!(attempt_errors(result)[0], Error::ParseAt { .. }));
+            primary_mock.assert();
+
+            // Test Case 5: Connection refused (HTTP IO error)
+            let client = test_client("http://localhost:1", "invalid_url");
+
+            let result = client.from_version(RegistryVersion::Latest).await;
+            let errors = attempt_errors(result);
+            assert!(matches!(errors[0], Error::Http(_)));
+            assert!(matches!(errors[1], Error::Http(_)));
+        }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+        async fn test_mirrors_and_headers() {
+            let registry_path = format!("/TheGraphNetworksRegistry_v{}_x.json", SCHEMA_VERSION);
+            let mut primary_server = Server::new_async().await;
+            let mut mirror_server = Server::new_async().await;
+
+            let client = RegistryClient::builder()
+                .primary_base_url(primary_server.url())
+                .no_fallback()
+                .mirror(mirror_server.url())
+                .user_agent("indexer-router/1.0")
+                .header("x-api-key", "secret")
+                .build()
+                .unwrap();
+            assert_eq!(
+                client.urls(RegistryVersion::Latest),
+                vec![
+                    format!("{}{}", primary_server.url(), registry_path),
+                    format!("{}{}", mirror_server.url(), registry_path)
+                ]
+            );
+
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .match_header("user-agent", "indexer-router/1.0")
+                .match_header("x-api-key", "secret")
+                .with_status(503)
+                .create_async()
+                .await;
+            let mirror_mock = mirror_server
+                .mock("GET", registry_path.as_str())
+                .match_header("user-agent", "indexer-router/1.0")
+                .match_header("x-api-key", "secret")
+                .with_status(200)
+                .with_body(REGISTRY_JSON)
+                .create_async()
+                .await;
+
+            // the configuration carries over to other worker threads
+            let registry = tokio::spawn(async move { client.from_version(RegistryVersion::Latest).await })
+                .await
+                .unwrap()
+                .expect("Should succeed using the mirror");
+            assert!(registry.get_network_by_graph_id("mainnet").is_some());
+            primary_mock.assert();
+            mirror_mock.assert();
+        }
+
+        #[tokio::test]
+        async fn test_from_url() {
+            let mut server = Server::new_async().await;
+            let client = RegistryClient::builder().header("x-api-key", "secret").build().unwrap();
+
+            let mock = server
+                .mock("GET", "/registry.json")
+                .match_header("x-api-key", "secret")
+                .with_status(200)
+                .with_body(REGISTRY_JSON)
+                .create_async()
+                .await;
+            let url = format!("{}/registry.json", server.url());
+            let registry = client.from_url(&url).await.unwrap();
+            assert!(registry.get_network_by_graph_id("mainnet").is_some());
+            mock.assert();
+
+            let missing = format!("{}/missing.json", server.url());
+            assert!(matches!(
+                client.from_url(&missing).await,
+                Err(Error::HttpStatus { url, status: 501 }) if url == missing
+            ));
+
+            server
+                .mock("GET", "/invalid.json")
+                .with_status(200)
+                .with_body("{")
+                .create_async()
+                .await;
+            let invalid = format!("{}/invalid.json", server.url());
+            assert!(matches!(client.from_url(&invalid).await, Err(Error::ParseAt { location, .. }) if location == invalid));
+        }
+
+        #[tokio::test]
+        async fn test_strict_schema_version() {
+            let registry_path = format!("/TheGraphNetworksRegistry_v{}_x.json", SCHEMA_VERSION);
+            let mut primary_server = Server::new_async().await;
+            let mut fallback_server = Server::new_async().await;
+            let client = RegistryClient::builder()
+                .primary_base_url(primary_server.url())
+                .fallback_base_url(fallback_server.url())
+                .strict(true)
+                .build()
+                .unwrap();
+
+            // the test registry has no schema version, so only the fallback one is accepted
+            let compatible_json = REGISTRY_JSON.replace(
+                "TheGraphNetworksRegistrySchema_vx_x.json",
+                &format!("TheGraphNetworksRegistrySchema_v{}.json", SCHEMA_VERSION),
+            );
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .with_body(REGISTRY_JSON)
+                .create_async()
+                .await;
+            let fallback_mock = fallback_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .with_body(compatible_json)
+                .create_async()
+                .await;
+
+            let fetched = client.fetch(RegistryVersion::Latest).await.unwrap();
+            assert_eq!(fetched.url, format!("{}{}", fallback_server.url(), registry_path));
+            assert!(fetched.registry.compatibility().is_compatible());
+            primary_mock.assert();
+            fallback_mock.assert();
+        }
+
+        #[test]
+        fn test_invalid_config() {
+            let result = RegistryClient::builder().header("bad header", "value").build();
+            assert!(matches!(result, Err(Error::InvalidConfig(_))));
+
+            let result = RegistryClient::builder().user_agent("bad\nagent").build();
+            assert!(matches!(result, Err(Error::InvalidConfig(_))));
+        }
+
+        #[tokio::test]
+        async fn test_cache_revalidation_and_stale() {
+            let registry_path = format!("/TheGraphNetworksRegistry_v{}_x.json", SCHEMA_VERSION);
+            let mut primary_server = Server::new_async().await;
+            let mut fallback_server = Server::new_async().await;
+            let cache_dir = test_cache_dir("async-cache");
+            let builder = RegistryClient::builder()
+                .primary_base_url(primary_server.url())
+                .fallback_base_url(fallback_server.url())
+                .cache_dir(&cache_dir);
+            let client = builder.clone().serve_stale(true).build().unwrap();
+
+            // Test Case 1: Nothing cached yet, full download
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .match_header("if-none-match", mockito::Matcher::Missing)
+                .with_status(200)
+                .with_header("etag", "\"v1\"")
+                .with_header("last-modified", "Wed, 01 Jan 2025 00:00:00 GMT")
+                .with_body(REGISTRY_JSON)
+                .create_async()
+                .await;
+
+            let fetched = client.fetch(RegistryVersion::Latest).await.unwrap();
+            assert_eq!(fetched.cache_status, CacheStatus::Fetched);
+            assert_eq!(fetched.url, format!("{}{}", primary_server.url(), registry_path));
+            primary_mock.assert();
+
+            // Test Case 2: Conditional request, cached copy reused on 304
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .match_header("if-none-match", "\"v1\"")
+                .match_header("if-modified-since", "Wed, 01 Jan 2025 00:00:00 GMT")
+                .with_status(304)
+                .create_async()
+                .await;
+
+            let fetched = client.fetch(RegistryVersion::Latest).await.unwrap();
+            assert_eq!(fetched.cache_status, CacheStatus::NotModified);
+            assert!(fetched.registry.get_network_by_graph_id("mainnet").is_some());
+            primary_mock.assert();
+
+            // Test Case 3: All sources fail, stale copy served only when allowed
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .with_status(500)
+                .expect(2)
+                .create_async()
+                .await;
+            let fallback_mock = fallback_server
+                .mock("GET", registry_path.as_str())
+                .with_status(500)
+                .expect(2)
+                .create_async()
+                .await;
+
+            let fetched = client.fetch(RegistryVersion::Latest).await.unwrap();
+            assert!(fetched.is_stale());
+            assert!(fetched.registry.get_network_by_graph_id("mainnet").is_some());
+
+            let strict_client = builder.build().unwrap();
+            let result = strict_client.fetch(RegistryVersion::Latest).await;
+            assert!(matches!(
+                attempt_errors(result)[..],
+                [Error::HttpStatus { status: 500, .. }, Error::HttpStatus { status: 500, .. }]
+            ));
+            primary_mock.assert();
+            fallback_mock.assert();
+
+            let _ = std::fs::remove_dir_all(cache_dir);
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    mod blocking_tests {
+        use super::*;
+        use mockito::Server;
+
+        #[test]
+        fn test_blocking_list_versions() {
+            let mut server = Server::new();
+            let client = test_client(&server.url(), &format!("{}/fallback", server.url()));
+            server.mock("GET", "/").with_status(404).create();
+            server
+                .mock("GET", "/fallback/")
+                .with_status(200)
+                .with_body(r#"[{"name": "TheGraphNetworksRegistry_v0_6_2.json"}, {"name": "TheGraphNetworksRegistry_v0_6_0.json"}]"#)
+                .create();
+
+            assert_eq!(
+                client.list_versions_blocking().unwrap(),
+                vec![RegistryVersionNumber::new(0, 6, 0), RegistryVersionNumber::new(0, 6, 2)]
+            );
+            assert_eq!(
+                client.latest_patch_blocking(0, 6).unwrap(),
+                Some(RegistryVersionNumber::new(0, 6, 2))
+            );
+        }
+
+        #[test]
+        fn test_blocking_fallback_with_mock_server() {
+            let registry_path = format!("/TheGraphNetworksRegistry_v{}_x.json", SCHEMA_VERSION);
+
+            let mut primary_server = Server::new();
+            let mut fallback_server = Server::new();
+            let client = test_client(&primary_server.url(), &fallback_server.url());
+
+            // Test Case 1: Primary succeeds
+            let primary_mock = primary_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .with_body(REGISTRY_JSON)
+                .create();
+            let fallback_mock = fallback_server.mock("GET", registry_path.as_str()).expect(0).create();
+
+            let registry = client
+                .from_version_blocking(RegistryVersion::Latest)
+                .expect("Should succeed with primary URL");
+            assert!(registry.get_network_by_graph_id("mainnet").is_some());
+            primary_mock.assert();
+            fallback_mock.assert();
+
+            // Test Case 2: Primary fails, fallback succeeds
+            let primary_mock = primary_server.mock("GET", registry_path.as_str()).with_status(500).create();
+            let fallback_mock = fallback_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .with_body(REGISTRY_JSON)
+                .create();
+
+            let registry = client
+                .from_version_blocking(RegistryVersion::Latest)
+                .expect("Should succeed using fallback URL");
+            assert!(registry.get_network_by_graph_id("eth").is_some());
+            primary_mock.assert();
+            fallback_mock.assert();
+
+            // Test Case 3: Both fail, each error is reported
+            let primary_mock = primary_server.mock("GET", registry_path.as_str()).with_status(404).create();
+            let fallback_mock = fallback_server
+                .mock("GET", registry_path.as_str())
+                .with_status(200)
+                .with_body("bye")
+                .create();
+
+            let result = client.from_version_blocking(RegistryVersion::Latest);
+            let errors = attempt_errors(result);
+            assert!(matches!(errors[0], Error::HttpStatus { status: 404, .. }));
+            assert!(matches!(errors[1], Error::ParseAt { .. }));
+            primary_mock.assert();
+            fallback_mock.assert();
+        }
+
+        #[test]
+        fn test_blocking_cache_revalidation() {
+            let mut primary_server = Server::new();
+            let cache_dir = test_cache_dir("blocking-cache");
+            let client = RegistryClient::builder()
+                .primary_base_url(primary_server.url())
+                .no_fallback()
+                .cache_dir(&cache_dir)
+                .serve_stale(true)
+                .build()
+                .unwrap();
+            let version = RegistryVersion::Exact("v0.5.3");
+
+            let primary_mock = primary_server
+                .mock("GET", "/TheGraphNetworksRegistry_v0_5_3.json")
+                .with_status(200)
+                .with_header("etag", "W/\"abc\"")
+                .with_body(REGISTRY_JSON)
+                .create();
+            assert_eq!(client.fetch_blocking(version).unwrap().cache_status, CacheStatus::Fetched);
+            primary_mock.assert();
+
+            let primary_mock = primary_server
+                .mock("GET", "/TheGraphNetworksRegistry_v0_5_3.json")
+                .match_header("if-none-match", "W/\"abc\"")
+                .with_status(304)
+                .create();
+            assert_eq!(client.fetch_blocking(version).unwrap().cache_status, CacheStatus::NotModified);
+            primary_mock.assert();
+
+            let primary_mock = primary_server
+                .mock("GET", "/TheGraphNetworksRegistry_v0_5_3.json")
+                .with_status(404)
+                .create();
+            assert_eq!(client.fetch_blocking(version).unwrap().cache_status, CacheStatus::Stale);
+            primary_mock.assert();
+
+            let _ = std::fs::remove_dir_all(cache_dir);
+        }
+
+        #[test]
+        fn test_blocking_exact_version() {
+            let mut primary_server = Server::new();
+            let fallback_server = Server::new();
+            let client = test_client(&primary_server.url(), &fallback_server.url());
+
+            let primary_mock = primary_server
+                .mock("GET", "/TheGraphNetworksRegistry_v0_5_3.json")
+                .with_status(200)
+                .with_body(REGISTRY_JSON)
+                .create();
+
+            let registry = client
+                .from_version_blocking(RegistryVersion::Exact("v0.5.3"))
+                .expect("Failed to fetch exact version");
+            assert_eq!(registry.networks.len(), 1);
+            primary_mock.assert();
+        }
+    }
+}
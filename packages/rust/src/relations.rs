@@ -0,0 +1,396 @@
+use crate::index::NetworksIndex;
+use crate::types::*;
+
+/// Graph of the `relations` between the networks of a registry
+///
+/// Relations point from a network to the network it is derived from, e.g. `sepolia` is
+/// `testnetOf` `mainnet`. Lookups accept graph ids (ids or aliases) and follow edges in
+/// either direction. Relations whose target doesn't resolve are kept as [`DanglingRelation`]s.
+///
+/// # Example
+///
+/// ```
+/// # use graph_networks_registry::NetworksRegistry;
+/// # fn example(registry: &NetworksRegistry) {
+/// let graph = registry.relation_graph();
+/// for testnet in graph.testnets_of("mainnet") {
+///     println!("{} is a testnet of Ethereum", testnet.id);
+/// }
+/// let l1 = graph.l1_of("arbitrum-one").map(|network| network.id.as_str());
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RelationGraph<'a> {
+    networks: &'a [Network],
+    index: NetworksIndex,
+    edges: Vec<Edge<'a>>,
+    outgoing: Vec<Vec<usize>>,
+    incoming: Vec<Vec<usize>>,
+    dangling: Vec<DanglingRelation<'a>>,
+}
+
+/// A relation between two networks of the registry
+#[derive(Debug, Clone, Copy)]
+pub struct RelationEdge<'a> {
+    /// Network the relation is declared on
+    pub from: &'a Network,
+
+    /// Kind of relation
+    pub kind: &'a RelationKind,
+
+    /// Network the relation points to
+    pub to: &'a Network,
+}
+
+/// A relation whose target isn't the id or alias of any network in the registry
+#[derive(Debug, Clone, Copy)]
+pub struct DanglingRelation<'a> {
+    /// Network the relation is declared on
+    pub network: &'a Network,
+
+    pub relation: &'a Relation,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Edge<'a> {
+    from: usize,
+    to: usize,
+    kind: &'a RelationKind,
+}
+
+impl NetworksRegistry {
+    /// Builds the graph of relations between networks
+    pub fn relation_graph(&self) -> RelationGraph<'_> {
+        RelationGraph::new(&self.networks)
+    }
+}
+
+impl<'a> RelationGraph<'a> {
+    fn new(networks: &'a [Network]) -> Self {
+        let mut graph = RelationGraph {
+            networks,
+            // resolves graph ids the same way as `get_network_by_graph_id`
+            index: NetworksIndex::build(networks),
+            edges: Vec::new(),
+            outgoing: vec![Vec::new(); networks.len()],
+            incoming: vec![Vec::new(); networks.len()],
+            dangling: Vec::new(),
+        };
+        for (from, network) in networks.iter().enumerate() {
+            for relation in network.relations.iter().flatten() {
+                match graph.index.graph_id(&relation.network) {
+                    Some(to) => {
+                        graph.outgoing[from].push(graph.edges.len());
+                        graph.incoming[to].push(graph.edges.len());
+                        graph.edges.push(Edge {
+                            from,
+                            to,
+                            kind: &relation.kind,
+                        });
+                    }
+                    None => graph.dangling.push(DanglingRelation { network, relation }),
+                }
+            }
+        }
+        graph
+    }
+
+    /// Returns all relations that resolve to a network, in the order of `networks`
+    pub fn edges(&self) -> impl Iterator<Item = RelationEdge<'a>> + '_ {
+        self.edges.iter().map(|edge| self.edge(edge))
+    }
+
+    /// Returns the relations declared on the network with graph id `id`
+    pub fn outgoing(&self, id: &str) -> Vec<RelationEdge<'a>> {
+        self.lookup(id, &self.outgoing)
+    }
+
+    /// Returns the relations other networks declare towards the network with graph id `id`
+    pub fn incoming(&self, id: &str) -> Vec<RelationEdge<'a>> {
+        self.lookup(id, &self.incoming)
+    }
+
+    /// Returns the networks `id` has a `kind` relation to, e.g. the mainnet of a testnet
+    pub fn related_to(&self, id: &str, kind: &RelationKind) -> Vec<&'a Network> {
+        self.outgoing(id)
+            .into_iter()
+            .filter(|edge| edge.kind == kind)
+            .map(|edge| edge.to)
+            .collect()
+    }
+
+    /// Returns the networks that have a `kind` relation to `id`, e.g. the testnets of a mainnet
+    pub fn related_from(&self, id: &str, kind: &RelationKind) -> Vec<&'a Network> {
+        self.incoming(id)
+            .into_iter()
+            .filter(|edge| edge.kind == kind)
+            .map(|edge| edge.from)
+            .collect()
+    }
+
+    /// Returns the testnets of the network with graph id `id`
+    pub fn testnets_of(&self, id: &str) -> Vec<&'a Network> {
+        self.related_from(id, &RelationKind::TestnetOf)
+    }
+
+    /// Returns the network that the testnet with graph id `id` is a testnet of
+    pub fn mainnet_of(&self, id: &str) -> Option<&'a Network> {
+        self.related_to(id, &RelationKind::TestnetOf).into_iter().next()
+    }
+
+    /// Returns the L1 under the L2 with graph id `id`, following `l2Of` relations down from L3s
+    ///
+    /// Returns `None` if `id` is not an L2, or if its `l2Of` relations lead back to a network
+    /// already visited.
+    pub fn l1_of(&self, id: &str) -> Option<&'a Network> {
+        let mut pos = self.index.graph_id(id)?;
+        let mut visited = vec![pos];
+        while let Some(parent) = self.outgoing[pos]
+            .iter()
+            .map(|&edge| &self.edges[edge])
+            .find(|edge| *edge.kind == RelationKind::L2Of)
+            .map(|edge| edge.to)
+        {
+            if visited.contains(&parent) {
+                return None;
+            }
+            visited.push(parent);
+            pos = parent;
+        }
+        (visited.len() > 1).then(|| &self.networks[pos])
+    }
+
+    /// Returns the L2s built on the network with graph id `id`
+    pub fn l2s_of(&self, id: &str) -> Vec<&'a Network> {
+        self.related_from(id, &RelationKind::L2Of)
+    }
+
+    /// Returns the beacon chain of the execution chain with graph id `id`
+    pub fn beacon_chain_of(&self, id: &str) -> Option<&'a Network> {
+        self.related_from(id, &RelationKind::BeaconOf).into_iter().next()
+    }
+
+    /// Returns the execution chain of the beacon chain with graph id `id`
+    pub fn execution_chain_of(&self, id: &str) -> Option<&'a Network> {
+        self.related_to(id, &RelationKind::BeaconOf).into_iter().next()
+    }
+
+    /// Returns the relations whose target doesn't resolve to a network
+    pub fn dangling_edges(&self) -> &[DanglingRelation<'a>] {
+        &self.dangling
+    }
+
+    /// Finds cycles of relations, e.g. two networks that are each other's testnet
+    ///
+    /// # Returns
+    ///
+    /// Each cycle as the networks along it, starting from the first one in `networks`.
+    /// A network related to itself is a cycle of one. An empty list means the graph is acyclic
+    pub fn find_cycles(&self) -> Vec<Vec<&'a Network>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            New,
+            OnPath,
+            Done,
+        }
+
+        let mut cycles = Vec::new();
+        let mut states = vec![State::New; self.networks.len()];
+        for start in 0..self.networks.len() {
+            if states[start] != State::New {
+                continue;
+            }
+            // depth-first search keeping the current path and the next edge to follow at each step
+            let mut path: Vec<(usize, usize)> = vec![(start, 0)];
+            states[start] = State::OnPath;
+            while let Some((node, next)) = path.last_mut() {
+                let node = *node;
+                let Some(&edge) = self.outgoing[node].get(*next) else {
+                    states[node] = State::Done;
+                    path.pop();
+                    continue;
+                };
+                *next += 1;
+                let to = self.edges[edge].to;
+                match states[to] {
+                    State::New => {
+                        states[to] = State::OnPath;
+                        path.push((to, 0));
+                    }
+                    State::OnPath => {
+                        let from = path.iter().position(|&(pos, _)| pos == to).unwrap_or(0);
+                        cycles.push(path[from..].iter().map(|&(pos, _)| &self.networks[pos]).collect());
+                    }
+                    State::Done => {}
+                }
+            }
+        }
+        cycles
+    }
+
+    fn lookup(&self, id: &str, edges: &[Vec<usize>]) -> Vec<RelationEdge<'a>> {
+        self.index
+            .graph_id(id)
+            .map(|pos| edges[pos].iter().map(|&edge| self.edge(&self.edges[edge])).collect())
+            .unwrap_or_default()
+    }
+
+    fn edge(&self, edge: &Edge<'a>) -> RelationEdge<'a> {
+        RelationEdge {
+            from: &self.networks[edge.from],
+            kind: edge.kind,
+            to: &self.networks[edge.to],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(id: &str, aliases: &[&str], relations: &[(&str, &str)]) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "aliases": aliases,
+            "fullName": id,
+            "shortName": id,
+            "caip2Id": format!("test:{}", id),
+            "networkType": "mainnet",
+            "issuanceRewards": false,
+            "services": {},
+            "relations": relations
+                .iter()
+                .map(|(kind, network)| serde_json::json!({ "kind": kind, "network": network }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    fn ids(networks: &[&Network]) -> Vec<String> {
+        networks.iter().map(|network| network.id.clone()).collect()
+    }
+
+    #[test]
+    fn test_relation_graph() {
+        let registry: NetworksRegistry = serde_json::from_value(serde_json::json!({
+            "$schema": "https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_vx_x.json",
+            "version": "x.x.x",
+            "title": "Test Registry",
+            "description": "Test Registry",
+            "updatedAt": "2025-01-01T00:00:00Z",
+            "networks": [
+                network("mainnet", &["eth"], &[]),
+                network("sepolia", &[], &[("testnetOf", "eth")]),
+                network("arbitrum-one", &["arb"], &[("l2Of", "mainnet")]),
+                network("arbitrum-sepolia", &[], &[("testnetOf", "arb"), ("l2Of", "sepolia")]),
+                network("mainnet-cl", &[], &[("beaconOf", "mainnet")]),
+                network("fork-a", &[], &[("forkedFrom", "fork-b"), ("other", "gone")]),
+                network("fork-b", &[], &[("forkedFrom", "fork-a")]),
+                network("loop", &[], &[("other", "loop")]),
+            ],
+        }))
+        .unwrap();
+        let graph = registry.relation_graph();
+
+        assert_eq!(ids(&graph.testnets_of("mainnet")), vec!["sepolia"]);
+        assert_eq!(ids(&graph.testnets_of("arbitrum-one")), vec!["arbitrum-sepolia"]);
+        assert_eq!(graph.mainnet_of("arbitrum-sepolia").unwrap().id, "arbitrum-one");
+        assert_eq!(graph.l1_of("arbitrum-one").unwrap().id, "mainnet");
+        assert_eq!(graph.l1_of("arbitrum-sepolia").unwrap().id, "sepolia");
+        assert!(graph.l1_of("mainnet").is_none());
+        assert_eq!(ids(&graph.l2s_of("eth")), vec!["arbitrum-one"]);
+        assert_eq!(graph.beacon_chain_of("eth").unwrap().id, "mainnet-cl");
+        assert_eq!(graph.execution_chain_of("mainnet-cl").unwrap().id, "mainnet");
+
+        assert_eq!(graph.outgoing("arbitrum-sepolia").len(), 2);
+        let incoming = graph.incoming("mainnet");
+        assert_eq!(incoming.len(), 3);
+        assert!(incoming.iter().all(|edge| edge.to.id == "mainnet"));
+        assert!(graph.incoming("unknown").is_empty());
+        assert_eq!(graph.edges().count(), 8);
+
+        let dangling = graph.dangling_edges();
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].network.id, "fork-a");
+        assert_eq!(dangling[0].relation.network, "gone");
+
+        let cycles: Vec<Vec<String>> = graph.find_cycles().iter().map(|cycle| ids(cycle)).collect();
+        assert_eq!(cycles, vec![vec!["fork-a", "fork-b"], vec!["loop"]]);
+    }
+
+    #[test]
+    fn test_graph_id_collision() {
+        // "sepolia" is the id of one network and an alias of an earlier one
+        let registry: NetworksRegistry = serde_json::from_value(serde_json::json!({
+            "$schema": "https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_vx_x.json",
+            "version": "x.x.x",
+            "title": "Test Registry",
+            "description": "Test Registry",
+            "updatedAt": "2025-01-01T00:00:00Z",
+            "networks": [
+                network("mainnet", &["sepolia"], &[]),
+                network("sepolia", &[], &[]),
+                network("arbitrum-sepolia", &[], &[("l2Of", "sepolia")]),
+            ],
+        }))
+        .unwrap();
+        let graph = registry.relation_graph();
+
+        assert_eq!(registry.get_network_by_graph_id("sepolia").unwrap().id, "sepolia");
+        assert_eq!(graph.l1_of("arbitrum-sepolia").unwrap().id, "sepolia");
+        assert_eq!(ids(&graph.l2s_of("sepolia")), vec!["arbitrum-sepolia"]);
+
+        let json = serde_json::to_string(&registry).unwrap();
+        let borrowed = crate::NetworksRegistryRef::from_json(&json).unwrap();
+        assert_eq!(borrowed.get_network_by_graph_id("sepolia").unwrap().id, "sepolia");
+    }
+
+    #[test]
+    fn test_layered_relation_graph() {
+        // an L3 on top of an L2, as published for arbitrum-one and xai
+        let registry: NetworksRegistry = serde_json::from_value(serde_json::json!({
+            "$schema": "https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_vx_x.json",
+            "version": "x.x.x",
+            "title": "Test Registry",
+            "description": "Test Registry",
+            "updatedAt": "2025-01-01T00:00:00Z",
+            "networks": [
+                network("mainnet", &["ethereum"], &[]),
+                network("mainnet-cl", &[], &[("beaconOf", "mainnet")]),
+                network("sepolia", &[], &[("testnetOf", "mainnet")]),
+                network("arbitrum-one", &[], &[("l2Of", "mainnet")]),
+                network("arbitrum-sepolia", &[], &[("testnetOf", "arbitrum-one"), ("l2Of", "sepolia")]),
+                network("xai", &[], &[("l2Of", "arbitrum-one")]),
+            ],
+        }))
+        .unwrap();
+        let graph = registry.relation_graph();
+
+        assert!(graph.dangling_edges().is_empty());
+        assert!(graph.find_cycles().is_empty());
+        assert_eq!(ids(&graph.l2s_of("ethereum")), vec!["arbitrum-one"]);
+        assert_eq!(ids(&graph.l2s_of("arbitrum-one")), vec!["xai"]);
+        assert_eq!(graph.l1_of("xai").unwrap().id, "mainnet");
+        assert_eq!(graph.l1_of("arbitrum-one").unwrap().id, "mainnet");
+        assert_eq!(graph.beacon_chain_of("mainnet").unwrap().id, "mainnet-cl");
+        assert_eq!(graph.mainnet_of("arbitrum-sepolia").unwrap().id, "arbitrum-one");
+        assert!(graph.mainnet_of("xai").is_none());
+
+        // L2s on top of each other without an L1
+        let registry: NetworksRegistry = serde_json::from_value(serde_json::json!({
+            "$schema": "https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_vx_x.json",
+            "version": "x.x.x",
+            "title": "Test Registry",
+            "description": "Test Registry",
+            "updatedAt": "2025-01-01T00:00:00Z",
+            "networks": [
+                network("l2-a", &[], &[("l2Of", "l2-b")]),
+                network("l2-b", &[], &[("l2Of", "l2-a")]),
+                network("l3", &[], &[("l2Of", "l2-a")]),
+            ],
+        }))
+        .unwrap();
+        let graph = registry.relation_graph();
+        assert!(graph.l1_of("l2-a").is_none());
+        assert!(graph.l1_of("l3").is_none());
+    }
+}
@@ -0,0 +1,182 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::key_order::{Ordered, Recorded};
+use crate::types::NetworksRegistry;
+
+impl NetworksRegistry {
+    /// Creates a new NetworksRegistry from a JSON string, remembering the order of its keys
+    ///
+    /// Serializing the registry writes keys back in the order they were read instead of schema
+    /// order, so regenerating a published file only changes what changed in the registry. Parsing
+    /// is slower than [`NetworksRegistry::from_json`], which should be used otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - A JSON string containing the networks registry data
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is invalid or doesn't match the expected format
+    pub fn from_json_preserving_order(json: &str) -> Result<Self, Error> {
+        let registry = serde_json::from_str::<Recorded>(json)
+            .and_then(Recorded::into_registry)
+            // parse again for an error with the location of the problem
+            .map_err(|err| serde_json::from_str::<NetworksRegistry>(json).err().unwrap_or(err))
+            .inspect_err(|_err| debug!(error = %_err, "failed to parse registry"))?;
+        registry.index.get(&registry.networks);
+        Ok(registry)
+    }
+
+    /// Serializes the registry to compact JSON
+    ///
+    /// Networks are written in the order of `networks` and keys in schema order, or in the order
+    /// they were read for registries loaded with [`NetworksRegistry::from_json_preserving_order`].
+    /// Fields that are `None` are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field can't be serialized
+    pub fn to_json(&self) -> Result<String, Error> {
+        let mut json = Vec::new();
+        self.write_ordered(&mut serde_json::Serializer::new(&mut json))?;
+        Ok(String::from_utf8(json).expect("serde_json writes UTF-8"))
+    }
+
+    /// Serializes the registry to JSON indented like the published registry files
+    ///
+    /// Uses the same ordering as [`NetworksRegistry::to_json`] with two spaces of indentation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field can't be serialized
+    pub fn to_json_pretty(&self) -> Result<String, Error> {
+        let mut json = Vec::new();
+        self.write_ordered(&mut serde_json::Serializer::pretty(&mut json))?;
+        Ok(String::from_utf8(json).expect("serde_json writes UTF-8"))
+    }
+
+    /// Writes the registry to a file as JSON
+    ///
+    /// The file is formatted like the published registry files, with a trailing newline, and can
+    /// be read back with [`NetworksRegistry::from_file`]. Writing a published registry that was
+    /// read with [`NetworksRegistry::from_json_preserving_order`] gives back the same bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the JSON file to create or replace
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field can't be serialized or the file can't be written
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut json = self.to_json_pretty()?;
+        json.push('\n');
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    fn write_ordered<S: serde::Serializer>(&self, serializer: S) -> Result<(), Error>
+    where
+        Error: From<S::Error>,
+    {
+        let value = serde_json::to_value(self)?;
+        Ordered::new(&value, &self.key_order).serialize(serializer)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Network, NetworkType};
+
+    const SAMPLE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../sample/TheGraphNetworksRegistry.json");
+
+    fn sample_preserving_order() -> NetworksRegistry {
+        NetworksRegistry::from_json_preserving_order(&std::fs::read_to_string(SAMPLE).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_to_json() {
+        let registry = NetworksRegistry::from_file(SAMPLE).unwrap();
+        let json = registry.to_json_pretty().unwrap();
+        assert!(json.starts_with("{\n  \"$schema\": \"https://networks-registry.thegraph.com/"));
+        assert!(!json.contains("null"));
+
+        // same contents as the published file, in the same order
+        let published: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(SAMPLE).unwrap()).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&json).unwrap(), published);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&registry.to_json().unwrap()).unwrap(),
+            published
+        );
+
+        // networks keep their recorded key order when they are moved around
+        let registry = sample_preserving_order();
+        let json = registry.to_json_pretty().unwrap();
+        let mut reversed = registry.clone();
+        reversed.networks.reverse();
+        let mut reparsed = NetworksRegistry::from_json_preserving_order(&reversed.to_json().unwrap()).unwrap();
+        reparsed.networks.reverse();
+        assert_eq!(reparsed.to_json_pretty().unwrap(), json);
+
+        assert!(matches!(
+            NetworksRegistry::from_json_preserving_order(r#"{"networks": 1}"#),
+            Err(Error::Parse(err)) if err.line() == 1
+        ));
+    }
+
+    #[test]
+    fn test_to_json_schema_order() {
+        let network = Network::builder()
+            .id("devnet")
+            .caip2_id("eip155:1337")
+            .full_name("Local Devnet")
+            .network_type(NetworkType::Devnet)
+            .alias("local")
+            .build()
+            .unwrap();
+        let registry = NetworksRegistry::builder()
+            .version("0.7.0")
+            .updated_at("2025-01-01T00:00:00Z")
+            .network(network)
+            .build()
+            .unwrap();
+        let json = registry.to_json().unwrap();
+        assert!(json.starts_with(r#"{"$schema":"https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_v"#));
+        assert!(json.contains(r#""version":"0.7.0","title":"The Graph networks registry","description":"This registry was generated"#));
+        assert!(json.contains(
+            r#""updatedAt":"2025-01-01T00:00:00Z","networks":[{"id":"devnet","shortName":"Local Devnet","fullName":"Local Devnet","aliases":["local"],"caip2Id":"eip155:1337","#
+        ));
+
+        // registries loaded without recording key order are written in schema order
+        let registry = NetworksRegistry::from_file(SAMPLE).unwrap();
+        let json = registry.to_json().unwrap();
+        assert!(json.contains(r#"{"id":"mainnet","shortName":"Ethereum","fullName":"Ethereum Mainnet","aliases":["#));
+
+        // keys added to a loaded network follow the ones that were read
+        let mut registry = sample_preserving_order();
+        let mainnet = registry.networks.iter_mut().find(|network| network.id == "mainnet").unwrap();
+        mainnet.extra.insert("genesisHash".to_string(), "0xd4e5".into());
+        let json = registry.to_json().unwrap();
+        assert!(json.contains(r#""icon":{"web3Icons":{"name":"ethereum"}},"genesisHash":"0xd4e5"}"#));
+    }
+
+    #[test]
+    fn test_to_file() {
+        let registry = sample_preserving_order();
+        let path = std::env::temp_dir().join(format!("graph-networks-registry-to-file-{}.json", std::process::id()));
+        registry.to_file(&path).unwrap();
+
+        // byte for byte the published file
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, std::fs::read_to_string(SAMPLE).unwrap());
+        std::fs::remove_file(path).unwrap();
+
+        let result = registry.to_file("/non/existent/path.json");
+        assert!(matches!(result, Err(Error::Io(_))));
+    }
+}
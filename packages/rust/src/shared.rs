@@ -0,0 +1,284 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+
+use crate::error::Error;
+use crate::registry_client::RegistryClient;
+use crate::types::NetworksRegistry;
+use crate::version::{RegistryVersion, RegistryVersionNumber};
+
+/// A registry that keeps itself up to date by polling in the background
+///
+/// The current registry is held behind an [`Arc`] and only replaced when a fetched
+/// registry has a different `version` or `updated_at`. Readers take cheap snapshots
+/// through [`RegistryHandle`]s, which also notify when the registry changes.
+///
+/// Polling stops when the `SharedRegistry` is dropped; handles keep the last registry.
+///
+/// # Example
+///
+/// ```no_run
+/// use graph_networks_registry::{RegistryClient, RegistryVersion, SharedRegistry};
+/// use std::time::Duration;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let shared = SharedRegistry::new(RegistryClient::new(), RegistryVersion::Latest, Duration::from_secs(300)).await?;
+///
+/// let mut handle = shared.handle();
+/// tokio::spawn(async move {
+///     while let Some(registry) = handle.changed().await {
+///         println!("Registry updated to {}", registry.version);
+///     }
+/// });
+///
+/// let mainnet = shared.current().get_network_by_graph_id("mainnet").cloned();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SharedRegistry {
+    poller: Arc<Poller>,
+    task: JoinHandle<()>,
+}
+
+/// Cheaply cloneable read handle to a [`SharedRegistry`]
+#[derive(Debug, Clone)]
+pub struct RegistryHandle {
+    receiver: watch::Receiver<Arc<NetworksRegistry>>,
+}
+
+#[derive(Debug)]
+struct Poller {
+    client: RegistryClient,
+    version: OwnedVersion,
+    sender: watch::Sender<Arc<NetworksRegistry>>,
+}
+
+/// Owned counterpart of [`RegistryVersion`] so the polling task can outlive the caller's borrow
+#[derive(Debug, Clone)]
+enum OwnedVersion {
+    Latest,
+    Exact(String),
+    Number(RegistryVersionNumber),
+}
+
+impl SharedRegistry {
+    /// Fetches the registry and starts polling `client` for updates every `poll_interval`
+    ///
+    /// Must be called from within a tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if `poll_interval` is zero, or an error if the initial fetch fails
+    pub async fn new(client: RegistryClient, version: RegistryVersion<'_>, poll_interval: Duration) -> Result<Self, Error> {
+        check_poll_interval(poll_interval)?;
+        let registry = client.from_version(version).await?;
+        Self::with_registry(client, version, registry, poll_interval)
+    }
+
+    /// Starts polling `client` for updates every `poll_interval`, starting from an already loaded `registry`
+    ///
+    /// Must be called from within a tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if `poll_interval` is zero
+    pub fn with_registry(
+        client: RegistryClient,
+        version: RegistryVersion<'_>,
+        registry: NetworksRegistry,
+        poll_interval: Duration,
+    ) -> Result<Self, Error> {
+        check_poll_interval(poll_interval)?;
+        let (sender, _) = watch::channel(Arc::new(registry));
+        let poller = Arc::new(Poller {
+            client,
+            version: version.into(),
+            sender,
+        });
+
+        let task = tokio::spawn({
+            let poller = poller.clone();
+            async move {
+                let mut interval = interval_at(Instant::now() + poll_interval, poll_interval);
+                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                loop {
+                    interval.tick().await;
+                    // a failed poll keeps serving the current registry until the next one
+                    if let Err(_err) = poller.poll().await {
+                        warn!(error = %_err, "failed to poll registry, keeping current version");
+                    }
+                }
+            }
+        });
+
+        Ok(SharedRegistry { poller, task })
+    }
+
+    /// Returns a snapshot of the current registry
+    pub fn current(&self) -> Arc<NetworksRegistry> {
+        self.poller.sender.borrow().clone()
+    }
+
+    /// Returns a read handle that can be cloned and moved to other tasks
+    pub fn handle(&self) -> RegistryHandle {
+        RegistryHandle {
+            receiver: self.poller.sender.subscribe(),
+        }
+    }
+
+    /// Fetches the registry now instead of waiting for the next poll
+    ///
+    /// Returns `true` if the registry changed and was replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fetch fails, the current registry is kept
+    pub async fn refresh(&self) -> Result<bool, Error> {
+        self.poller.poll().await
+    }
+}
+
+impl Drop for SharedRegistry {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl RegistryHandle {
+    /// Returns a snapshot of the current registry
+    pub fn current(&self) -> Arc<NetworksRegistry> {
+        self.receiver.borrow().clone()
+    }
+
+    /// Waits until the registry is replaced and returns the new one
+    ///
+    /// Returns `None` once the [`SharedRegistry`] has been dropped and no more updates will come.
+    pub async fn changed(&mut self) -> Option<Arc<NetworksRegistry>> {
+        self.receiver.changed().await.ok()?;
+        Some(self.receiver.borrow_and_update().clone())
+    }
+}
+
+impl Poller {
+    async fn poll(&self) -> Result<bool, Error> {
+        let registry = self.client.from_version(self.version.as_version()).await?;
+        Ok(self.sender.send_if_modified(|current| {
+            if current.version == registry.version && current.updated_at == registry.updated_at {
+                return false;
+            }
+            info!(version = %registry.version, updated_at = %registry.updated_at, "registry updated");
+            *current = Arc::new(registry);
+            true
+        }))
+    }
+}
+
+fn check_poll_interval(poll_interval: Duration) -> Result<(), Error> {
+    if poll_interval.is_zero() {
+        return Err(Error::InvalidConfig("poll interval must be greater than zero".to_string()));
+    }
+    Ok(())
+}
+
+impl OwnedVersion {
+    fn as_version(&self) -> RegistryVersion<'_> {
+        match self {
+            OwnedVersion::Latest => RegistryVersion::Latest,
+            OwnedVersion::Exact(version) => RegistryVersion::Exact(version),
+            OwnedVersion::Number(version) => RegistryVersion::Number(*version),
+        }
+    }
+}
+
+impl From<RegistryVersion<'_>> for OwnedVersion {
+    fn from(version: RegistryVersion<'_>) -> Self {
+        match version {
+            RegistryVersion::Latest => OwnedVersion::Latest,
+            RegistryVersion::Exact(version) => OwnedVersion::Exact(version.to_string()),
+            RegistryVersion::Number(version) => OwnedVersion::Number(version),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::REGISTRY_JSON;
+    use crate::version::SCHEMA_VERSION;
+    use mockito::Server;
+
+    #[tokio::test]
+    async fn test_shared_registry_updates() {
+        let registry_path = format!("/TheGraphNetworksRegistry_v{}_x.json", SCHEMA_VERSION);
+        let mut server = Server::new_async().await;
+        let client = RegistryClient::builder()
+            .primary_base_url(server.url())
+            .no_fallback()
+            .build()
+            .unwrap();
+
+        let initial_mock = server
+            .mock("GET", registry_path.as_str())
+            .with_status(200)
+            .with_body(REGISTRY_JSON)
+            .create_async()
+            .await;
+        let shared = SharedRegistry::new(client, RegistryVersion::Latest, Duration::from_millis(50))
+            .await
+            .unwrap();
+        let mut handle = shared.handle();
+        assert_eq!(handle.current().updated_at, "2025-01-01T00:00:00Z");
+
+        // Same version and updated_at, nothing is replaced
+        let before = shared.current();
+        assert!(!shared.refresh().await.unwrap());
+        assert!(Arc::ptr_eq(&before, &shared.current()));
+        initial_mock.remove_async().await;
+
+        // Failed polls keep the current registry
+        let failing_mock = server.mock("GET", registry_path.as_str()).with_status(500).create_async().await;
+        assert!(shared.refresh().await.is_err());
+        assert!(Arc::ptr_eq(&before, &shared.current()));
+        failing_mock.remove_async().await;
+
+        // Background polling picks up the new registry and notifies handles
+        let updated_json = REGISTRY_JSON.replace("2025-01-01T00:00:00Z", "2025-02-01T00:00:00Z");
+        server
+            .mock("GET", registry_path.as_str())
+            .with_status(200)
+            .with_body(updated_json)
+            .create_async()
+            .await;
+        let updated = tokio::time::timeout(Duration::from_secs(5), handle.changed())
+            .await
+            .expect("Registry should be updated by the poller")
+            .unwrap();
+        assert_eq!(updated.updated_at, "2025-02-01T00:00:00Z");
+        assert_eq!(shared.current().updated_at, "2025-02-01T00:00:00Z");
+
+        // Handles see the end of updates once the shared registry is dropped
+        drop(shared);
+        assert!(handle.changed().await.is_none());
+        assert_eq!(handle.current().updated_at, "2025-02-01T00:00:00Z");
+    }
+
+    #[tokio::test]
+    async fn test_zero_poll_interval() {
+        let registry = NetworksRegistry::from_json(REGISTRY_JSON).unwrap();
+        let result = SharedRegistry::with_registry(RegistryClient::new(), RegistryVersion::Latest, registry, Duration::ZERO);
+        assert!(matches!(result, Err(Error::InvalidConfig(_))));
+
+        // rejected before anything is fetched
+        let client = RegistryClient::builder()
+            .primary_base_url("http://127.0.0.1:1")
+            .no_fallback()
+            .build()
+            .unwrap();
+        let result = SharedRegistry::new(client, RegistryVersion::Latest, Duration::ZERO).await;
+        assert!(matches!(result, Err(Error::InvalidConfig(_))));
+    }
+}
@@ -0,0 +1,177 @@
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "chrono")]
+use crate::types::{Firehose, GraphNode, Network, NetworksRegistry, TokenApi};
+
+#[cfg(feature = "chrono")]
+impl NetworksRegistry {
+    /// Returns `updated_at` as a date and time in UTC, `None` if it isn't an RFC 3339 date-time
+    pub fn updated_at_datetime(&self) -> Option<DateTime<Utc>> {
+        parse(&self.updated_at)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Firehose {
+    /// Returns `deprecated_at` as a date and time in UTC, `None` if unset or not an RFC 3339 date-time
+    pub fn deprecated_at_datetime(&self) -> Option<DateTime<Utc>> {
+        self.deprecated_at.as_deref().and_then(parse)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl GraphNode {
+    /// Returns `deprecated_at` as a date and time in UTC, `None` if unset or not an RFC 3339 date-time
+    pub fn deprecated_at_datetime(&self) -> Option<DateTime<Utc>> {
+        self.deprecated_at.as_deref().and_then(parse)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TokenApi {
+    /// Returns `deprecated_at` as a date and time in UTC, `None` if unset or not an RFC 3339 date-time
+    pub fn deprecated_at_datetime(&self) -> Option<DateTime<Utc>> {
+        self.deprecated_at.as_deref().and_then(parse)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Network {
+    /// Returns `true` if the network is deprecated in Firehose software at `now`
+    ///
+    /// A `deprecatedAt` that isn't an RFC 3339 date-time is ignored, see [`NetworksRegistry::validate`].
+    pub fn is_deprecated_in_firehose_at(&self, now: DateTime<Utc>) -> bool {
+        is_reached(self.firehose.as_ref().and_then(Firehose::deprecated_at_datetime), now)
+    }
+
+    /// Returns `true` if the network is deprecated in Graph Node software at `now`
+    pub fn is_deprecated_in_graph_node_at(&self, now: DateTime<Utc>) -> bool {
+        is_reached(self.graph_node.as_ref().and_then(GraphNode::deprecated_at_datetime), now)
+    }
+
+    /// Returns `true` if the network is deprecated in Token API software at `now`
+    pub fn is_deprecated_in_token_api_at(&self, now: DateTime<Utc>) -> bool {
+        is_reached(self.token_api.as_ref().and_then(TokenApi::deprecated_at_datetime), now)
+    }
+}
+
+#[cfg(feature = "chrono")]
+pub(crate) fn is_reached(deprecated_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    deprecated_at.is_some_and(|deprecated_at| deprecated_at <= now)
+}
+
+#[cfg(feature = "chrono")]
+pub(crate) fn parse(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|datetime| datetime.with_timezone(&Utc))
+}
+
+/// Checks a timestamp field of a loaded registry, which has to be an RFC 3339 date-time
+pub(crate) fn is_valid(value: &str) -> bool {
+    is_rfc3339(value)
+}
+
+/// Checks for an RFC 3339 date-time, e.g. 2025-01-01T00:00:00Z or 2025-01-01T00:00:00.123+02:00
+fn is_rfc3339(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    let number = |range: std::ops::Range<usize>| -> Option<u32> {
+        let digits = bytes.get(range)?;
+        digits
+            .iter()
+            .try_fold(0u32, |n, &b| b.is_ascii_digit().then(|| n * 10 + u32::from(b - b'0')))
+    };
+    let separators = [(4, b'-'), (7, b'-'), (13, b':'), (16, b':')];
+    if bytes.len() < 20 || !matches!(bytes[10], b'T' | b't') || separators.iter().any(|&(i, c)| bytes[i] != c) {
+        return false;
+    }
+    let (Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(second)) = (
+        number(0..4),
+        number(5..7),
+        number(8..10),
+        number(11..13),
+        number(14..16),
+        number(17..19),
+    ) else {
+        return false;
+    };
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => return false,
+    };
+    if day == 0 || day > days_in_month || hour > 23 || minute > 59 || second > 60 {
+        return false;
+    }
+
+    let mut rest = &value[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return false;
+        }
+        rest = &fraction[digits..];
+    }
+    match rest.as_bytes() {
+        [b'Z' | b'z'] => true,
+        [b'+' | b'-', _, _, b':', _, _] => {
+            let offset = value.len() - 5;
+            matches!((number(offset..offset + 2), number(offset + 3..offset + 5)), (Some(hours), Some(minutes)) if hours <= 23 && minutes <= 59)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc3339() {
+        assert!(is_rfc3339("2025-07-02T19:53:42.517Z"));
+        assert!(is_rfc3339("2024-02-29T23:59:60-05:30"));
+        assert!(!is_rfc3339("2023-02-29T00:00:00Z"));
+        assert!(!is_rfc3339("2025-07-02 19:53:42Z"));
+        assert!(!is_rfc3339("2025-07-02T19:53:42"));
+        assert!(!is_rfc3339("2025-07-02T19:53:42.Z"));
+        assert!(!is_rfc3339("2025-07-02T19:53:42+2:00"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_timestamps() {
+        use crate::client::tests::REGISTRY_JSON;
+        use chrono::TimeZone;
+
+        let json = REGISTRY_JSON
+            .replace("2025-01-01T00:00:00Z", "2025-07-02T19:53:42.517Z")
+            .replace(r#""issuanceRewards""#, r#""firehose": { "blockType": "b", "bufUrl": "u", "bytesEncoding": "hex", "deprecatedAt": "2025-04-23T02:00:00+02:00" }, "issuanceRewards""#);
+        let registry = NetworksRegistry::from_json(&json).unwrap();
+        assert_eq!(
+            registry.updated_at_datetime(),
+            Some(Utc.with_ymd_and_hms(2025, 7, 2, 19, 53, 42).unwrap() + chrono::Duration::milliseconds(517))
+        );
+
+        // Fields keep the published strings, offsets included
+        let value = serde_json::to_value(&registry).unwrap();
+        assert_eq!(value["updatedAt"], "2025-07-02T19:53:42.517Z");
+        assert_eq!(value["networks"][0]["firehose"]["deprecatedAt"], "2025-04-23T02:00:00+02:00");
+
+        let mainnet = &registry.networks[0];
+        let deprecated_at = Utc.with_ymd_and_hms(2025, 4, 23, 0, 0, 0).unwrap();
+        assert_eq!(mainnet.firehose.as_ref().unwrap().deprecated_at_datetime(), Some(deprecated_at));
+        assert!(!mainnet.is_deprecated_in_firehose_at(deprecated_at - chrono::Duration::seconds(1)));
+        assert!(mainnet.is_deprecated_in_firehose_at(deprecated_at));
+        assert!(!mainnet.is_deprecated_in_graph_node_at(deprecated_at));
+        assert!(!mainnet.is_deprecated_in_token_api_at(deprecated_at));
+
+        // Invalid timestamps still parse, without a date and time
+        let registry = NetworksRegistry::from_json(&json.replace("2025-04-23T02:00:00+02:00", "soon")).unwrap();
+        assert_eq!(registry.networks[0].firehose.as_ref().unwrap().deprecated_at_datetime(), None);
+        assert!(!registry.networks[0].is_deprecated_in_firehose_at(deprecated_at));
+        assert_eq!(registry.validate().len(), 1);
+    }
+}
@@ -32,6 +32,18 @@ pub struct NetworksRegistry {
 
     /// Version of the registry
     pub version: String,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+
+    /// Lookup index over `networks`, built on first use
+    #[serde(skip)]
+    pub(crate) index: crate::index::LazyIndex,
+
+    /// Order of the keys as read, used when serializing back to JSON
+    #[serde(skip)]
+    pub(crate) key_order: crate::key_order::KeyOrder,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,31 +51,38 @@ pub struct NetworksRegistry {
 pub struct Network {
     /// [optional] List of possible aliases for the network id, e.g. ethereum, eth, mainnet,
     /// eth-mainnet
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub aliases: Option<Vec<String>>,
 
     /// List of API URLs for the network, i.e. Etherescan-like API to get ABI. Use
     /// {CUSTOM_API_KEY} as a placeholder for a private API key
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub api_urls: Option<Vec<ApiUrl>>,
 
     /// CAIP-2 Chain ID, e.g. eip155:1, bip122:000000000019d6689c085ae165831e93
     pub caip2_id: String,
 
     /// URL to the chain documentation
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub docs_url: Option<String>,
 
     /// URLs for the block explorers
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub explorer_urls: Option<Vec<String>>,
 
     /// Firehose block information
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub firehose: Option<Firehose>,
 
     /// Display name of the network, e.g. Ethereum Mainnet, Bitcoin Testnet
     pub full_name: String,
 
     /// Graph Node specific configuration information
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub graph_node: Option<GraphNode>,
 
     /// Icons for the network
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub icon: Option<Icon>,
 
     /// Established name of the network in The Graph ecosystem, e.g. mainnet, btc,
@@ -71,25 +90,30 @@ pub struct Network {
     pub id: String,
 
     /// Documentation to run indexer components for this network
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub indexer_docs_urls: Option<Vec<IndexerDocsUrl>>,
 
     /// Issuance rewards on the Graph Network for this chain
     pub issuance_rewards: bool,
 
     /// Symbol of the native token
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub native_token: Option<String>,
 
     /// Whether the network is a mainnet/testnet/devnet
     pub network_type: NetworkType,
 
     /// Relations to other networks in the registry
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub relations: Option<Vec<Relation>>,
 
     /// List of RPC URLs for the chain. Use {CUSTOM_API_KEY} as a placeholder for a private API
     /// key
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub rpc_urls: Option<Vec<String>>,
 
     /// Second display name of the network, e.g. Sepolia, Nova
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub second_name: Option<String>,
 
     /// Services available for the network in the ecosystem
@@ -99,7 +123,12 @@ pub struct Network {
     pub short_name: String,
 
     /// Token API specific configuration information
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub token_api: Option<TokenApi>,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -108,11 +137,14 @@ pub struct ApiUrl {
     pub kind: ApiUrlKind,
 
     pub url: String,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Kind of API
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ApiUrlKind {
     Blockscout,
 
@@ -123,6 +155,9 @@ pub enum ApiUrlKind {
     Other,
 
     Subscan,
+
+    /// Value added to the schema after this version of the crate, kept as is
+    Unknown(String),
 }
 
 /// Firehose block information
@@ -130,6 +165,7 @@ pub enum ApiUrlKind {
 #[serde(rename_all = "camelCase")]
 pub struct Firehose {
     /// Block features supported by the network
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub block_features: Option<Vec<String>>,
 
     /// Block type, e.g. sf.ethereum.type.v2.Block
@@ -142,18 +178,24 @@ pub struct Firehose {
     pub bytes_encoding: BytesEncoding,
 
     /// [optional] Timestamp when the network was deprecated in Firehose software
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub deprecated_at: Option<String>,
 
     /// [optional] Whether there is support for extended EVM block model
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub evm_extended_model: Option<bool>,
 
     /// First available block information
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub first_streamable_block: Option<FirstStreamableBlock>,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Bytes encoding, e.g. hex, 0xhex, base58
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BytesEncoding {
     Base58,
 
@@ -163,8 +205,10 @@ pub enum BytesEncoding {
 
     Other,
 
-    #[serde(rename = "0xhex")]
     The0Xhex,
+
+    /// Value added to the schema after this version of the crate, kept as is
+    Unknown(String),
 }
 
 /// First available block information
@@ -175,6 +219,10 @@ pub struct FirstStreamableBlock {
 
     /// Id of the first streamable block either in 0x-prefixed hex or base58
     pub id: String,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Graph Node specific configuration information
@@ -182,15 +230,20 @@ pub struct FirstStreamableBlock {
 #[serde(rename_all = "camelCase")]
 pub struct GraphNode {
     /// [optional] Timestamp when the network was deprecated in Graph Node software
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub deprecated_at: Option<String>,
 
     /// [optional] Protocol name in graph-node, e.g. ethereum, near, arweave
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub protocol: Option<Protocol>,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// [optional] Protocol name in graph-node, e.g. ethereum, near, arweave
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Protocol {
     Arweave,
 
@@ -203,6 +256,9 @@ pub enum Protocol {
     Other,
 
     Starknet,
+
+    /// Value added to the schema after this version of the crate, kept as is
+    Unknown(String),
 }
 
 /// Icons for the network
@@ -210,7 +266,12 @@ pub enum Protocol {
 #[serde(rename_all = "camelCase")]
 pub struct Icon {
     /// Web3Icons icon - see https://github.com/0xa3k5/web3icons
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub web3_icons: Option<Web3Icons>,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Web3Icons icon - see https://github.com/0xa3k5/web3icons
@@ -220,21 +281,30 @@ pub struct Web3Icons {
     pub name: String,
 
     /// Variants of the icon, if none specified - all are available
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub variants: Option<Vec<String>>,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexerDocsUrl {
     /// Docs description, e.g. Arbitrum 101
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
     /// URL to the documentation, e.g. https://docs.infradao.com/archive-nodes-101/arbitrum
     pub url: String,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Whether the network is a mainnet/testnet/devnet
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum NetworkType {
     Beacon,
 
@@ -243,6 +313,9 @@ pub enum NetworkType {
     Mainnet,
 
     Testnet,
+
+    /// Value added to the schema after this version of the crate, kept as is
+    Unknown(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -252,34 +325,33 @@ pub struct Relation {
 
     /// ID of the related network, e.g. mainnet, near-mainnet
     pub network: String,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Kind of relation
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum RelationKind {
-    #[serde(rename = "beaconOf")]
     BeaconOf,
 
-    #[serde(rename = "evmOf")]
     EvmOf,
 
-    #[serde(rename = "forkedFrom")]
     ForkedFrom,
 
-    #[serde(rename = "l2Of")]
     L2Of,
 
     Other,
 
-    #[serde(rename = "shardOf")]
     ShardOf,
 
-    #[serde(rename = "svmOf")]
     SvmOf,
 
-    #[serde(rename = "testnetOf")]
     TestnetOf,
+
+    /// Value added to the schema after this version of the crate, kept as is
+    Unknown(String),
 }
 
 /// Services available for the network in the ecosystem
@@ -287,19 +359,28 @@ pub enum RelationKind {
 #[serde(rename_all = "camelCase")]
 pub struct Services {
     /// Firehose gRPC URLs, e.g. eth.firehose.pinax.network:443
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub firehose: Option<Vec<String>>,
 
     /// Substreams-based subgraphs studio deployment URLs, e.g. https://api.thegraph.com/deploy
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub sps: Option<Vec<String>>,
 
     /// Subgraph studio deployment URLs, e.g. https://api.thegraph.com/deploy
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub subgraphs: Option<Vec<String>>,
 
     /// Substreams gRPC URLs, e.g. eth.substreams.pinax.network:443
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub substreams: Option<Vec<String>>,
 
     /// Token API URLs, e.g. https://token-api.thegraph.com
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub token_api: Option<Vec<String>>,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Token API specific configuration information
@@ -307,17 +388,23 @@ pub struct Services {
 #[serde(rename_all = "camelCase")]
 pub struct TokenApi {
     /// [optional] Timestamp when the network was deprecated in Token API software
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub deprecated_at: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub features: Option<Vec<Feature>>,
 
     /// Network ID in Token API, has to be an ID or alias of an existing network
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub network_id: Option<String>,
+
+    /// Fields not known to this version of the crate, kept so they serialize back unchanged
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// List of Token API features supported
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Feature {
     Dexes,
 
@@ -326,4 +413,7 @@ pub enum Feature {
     Other,
 
     Tokens,
+
+    /// Value added to the schema after this version of the crate, kept as is
+    Unknown(String),
 }
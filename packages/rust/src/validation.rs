@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::timestamp;
+use crate::types::*;
+
+/// A problem found by [`NetworksRegistry::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// More than one network has the same `id`
+    DuplicateId { id: String },
+
+    /// An alias of `network` is the id or an alias of `other`
+    AliasCollision { alias: String, network: String, other: String },
+
+    /// More than one network has the same `caip2_id`
+    DuplicateCaip2Id { caip2_id: String, network: String, other: String },
+
+    /// `caip2_id` is not a valid CAIP-2 chain id
+    MalformedCaip2Id { network: String, caip2_id: String },
+
+    /// A relation of `network` points to a network that is not in the registry
+    UnresolvedRelation {
+        network: String,
+        kind: RelationKind,
+        target: String,
+    },
+
+    /// The Token API `network_id` of `network` is not an id or alias in the registry
+    UnresolvedTokenApiNetwork { network: String, network_id: String },
+
+    /// A timestamp field is not a valid RFC 3339 date-time. `network` is `None` for registry-level fields
+    InvalidTimestamp {
+        network: Option<String>,
+        field: &'static str,
+        value: String,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::DuplicateId { id } => write!(f, "duplicate network id \"{}\"", id),
+            ValidationIssue::AliasCollision { alias, network, other } => {
+                write!(
+                    f,
+                    "alias \"{}\" of network \"{}\" collides with network \"{}\"",
+                    alias, network, other
+                )
+            }
+            ValidationIssue::DuplicateCaip2Id { caip2_id, network, other } => {
+                write!(
+                    f,
+                    "network \"{}\" has the same CAIP-2 id \"{}\" as network \"{}\"",
+                    network, caip2_id, other
+                )
+            }
+            ValidationIssue::MalformedCaip2Id { network, caip2_id } => {
+                write!(f, "network \"{}\" has malformed CAIP-2 id \"{}\"", network, caip2_id)
+            }
+            ValidationIssue::UnresolvedRelation { network, kind, target } => {
+                write!(f, "network \"{}\" has {} relation to unknown network \"{}\"", network, kind, target)
+            }
+            ValidationIssue::UnresolvedTokenApiNetwork { network, network_id } => {
+                write!(
+                    f,
+                    "network \"{}\" has Token API network id \"{}\" that is not in the registry",
+                    network, network_id
+                )
+            }
+            ValidationIssue::InvalidTimestamp {
+                network: Some(network),
+                field,
+                value,
+            } => write!(f, "network \"{}\" has invalid {} timestamp \"{}\"", network, field, value),
+            ValidationIssue::InvalidTimestamp {
+                network: None,
+                field,
+                value,
+            } => {
+                write!(f, "registry has invalid {} timestamp \"{}\"", field, value)
+            }
+        }
+    }
+}
+
+impl NetworksRegistry {
+    /// Checks the registry for problems that parsing alone doesn't catch
+    ///
+    /// Finds duplicate ids and CAIP-2 ids, aliases that collide with other networks, relations and
+    /// Token API network ids that don't resolve, malformed CAIP-2 ids and unparsable timestamps.
+    ///
+    /// # Returns
+    ///
+    /// Returns all issues found, grouped by check: the registry timestamp, duplicate ids, then aliases
+    /// and CAIP-2 ids, then relations, Token API network ids and deprecation timestamps. Within each
+    /// group issues follow the order of `networks`. An empty list means the registry is valid
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if !timestamp::is_valid(&self.updated_at) {
+            issues.push(ValidationIssue::InvalidTimestamp {
+                network: None,
+                field: "updatedAt",
+                value: self.updated_at.to_string(),
+            });
+        }
+
+        // graph ids (ids and aliases) and CAIP-2 ids mapped to the first network that uses them
+        let mut graph_ids: HashMap<&str, &str> = HashMap::new();
+        let mut caip2_ids: HashMap<&str, &str> = HashMap::new();
+        for network in &self.networks {
+            if graph_ids.insert(&network.id, &network.id).is_some() {
+                issues.push(ValidationIssue::DuplicateId { id: network.id.clone() });
+            }
+        }
+        for network in &self.networks {
+            for alias in network.aliases.iter().flatten() {
+                match graph_ids.get(alias.as_str()) {
+                    Some(&other) if other != network.id => issues.push(ValidationIssue::AliasCollision {
+                        alias: alias.clone(),
+                        network: network.id.clone(),
+                        other: other.to_string(),
+                    }),
+                    Some(_) => {}
+                    None => {
+                        graph_ids.insert(alias, &network.id);
+                    }
+                }
+            }
+
+            if let Some(other) = caip2_ids.insert(&network.caip2_id, &network.id) {
+                issues.push(ValidationIssue::DuplicateCaip2Id {
+                    caip2_id: network.caip2_id.clone(),
+                    network: network.id.clone(),
+                    other: other.to_string(),
+                });
+                caip2_ids.insert(&network.caip2_id, other);
+            }
+            if network.caip2().is_err() {
+                issues.push(ValidationIssue::MalformedCaip2Id {
+                    network: network.id.clone(),
+                    caip2_id: network.caip2_id.clone(),
+                });
+            }
+        }
+
+        for network in &self.networks {
+            for relation in network.relations.iter().flatten() {
+                if !graph_ids.contains_key(relation.network.as_str()) {
+                    issues.push(ValidationIssue::UnresolvedRelation {
+                        network: network.id.clone(),
+                        kind: relation.kind.clone(),
+                        target: relation.network.clone(),
+                    });
+                }
+            }
+            if let Some(network_id) = network.token_api.as_ref().and_then(|token_api| token_api.network_id.as_ref()) {
+                if !graph_ids.contains_key(network_id.as_str()) {
+                    issues.push(ValidationIssue::UnresolvedTokenApiNetwork {
+                        network: network.id.clone(),
+                        network_id: network_id.clone(),
+                    });
+                }
+            }
+
+            deprecation_timestamp_issues(network, &mut issues);
+        }
+
+        issues
+    }
+}
+
+impl Network {
+    /// Checks the network for problems that don't depend on the rest of the registry
+    ///
+    /// Finds a malformed CAIP-2 id and unparsable timestamps. Use [`NetworksRegistry::validate`] to
+    /// also check ids, aliases and relations against the other networks.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        if self.caip2().is_err() {
+            issues.push(ValidationIssue::MalformedCaip2Id {
+                network: self.id.clone(),
+                caip2_id: self.caip2_id.clone(),
+            });
+        }
+        deprecation_timestamp_issues(self, &mut issues);
+        issues
+    }
+}
+
+fn deprecation_timestamp_issues(network: &Network, issues: &mut Vec<ValidationIssue>) {
+    let deprecated_at = [
+        (
+            "firehose.deprecatedAt",
+            network.firehose.as_ref().and_then(|f| f.deprecated_at.as_ref()),
+        ),
+        (
+            "graphNode.deprecatedAt",
+            network.graph_node.as_ref().and_then(|g| g.deprecated_at.as_ref()),
+        ),
+        (
+            "tokenApi.deprecatedAt",
+            network.token_api.as_ref().and_then(|t| t.deprecated_at.as_ref()),
+        ),
+    ];
+    for (field, value) in deprecated_at {
+        if let Some(value) = value.filter(|value| !timestamp::is_valid(value)) {
+            issues.push(ValidationIssue::InvalidTimestamp {
+                network: Some(network.id.clone()),
+                field,
+                value: value.to_string(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVALID_REGISTRY_JSON: &str = r#"{
+        "$schema": "https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_vx_x.json",
+        "version": "x.x.x",
+        "title": "Test Registry",
+        "description": "Test Registry",
+        "updatedAt": "2025-13-01T00:00:00Z",
+        "networks": [
+            {
+                "id": "mainnet",
+                "fullName": "Ethereum Mainnet",
+                "shortName": "Ethereum",
+                "caip2Id": "eip155:1",
+                "networkType": "mainnet",
+                "aliases": ["ethereum", "eth"],
+                "issuanceRewards": true,
+                "services": {},
+                "firehose": {
+                    "blockType": "sf.ethereum.type.v2.Block",
+                    "bufUrl": "https://buf.build/streamingfast/firehose-ethereum",
+                    "bytesEncoding": "hex",
+                    "deprecatedAt": "2025-02-30T00:00:00Z"
+                },
+                "tokenApi": { "networkId": "eth", "deprecatedAt": "2025-01-01T00:00:00.5+01:00" }
+            },
+            {
+                "id": "sepolia",
+                "fullName": "Ethereum Sepolia",
+                "shortName": "Sepolia",
+                "caip2Id": "eip155:1",
+                "networkType": "testnet",
+                "aliases": ["eth", "mainnet", "sep"],
+                "issuanceRewards": false,
+                "services": {},
+                "relations": [
+                    { "kind": "testnetOf", "network": "ethereum" },
+                    { "kind": "l2Of", "network": "goerli" }
+                ],
+                "graphNode": { "deprecatedAt": "yesterday" },
+                "tokenApi": { "networkId": "sepolia-testnet" }
+            },
+            {
+                "id": "sepolia",
+                "fullName": "Duplicate",
+                "shortName": "Duplicate",
+                "caip2Id": "EIP155 11155111",
+                "networkType": "testnet",
+                "issuanceRewards": false,
+                "services": {}
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_validate() {
+        let registry = NetworksRegistry::from_json(INVALID_REGISTRY_JSON).expect("Failed to parse registry");
+        let issues = registry.validate();
+
+        let alias_collision = |alias: &str, other: &str| ValidationIssue::AliasCollision {
+            alias: alias.to_string(),
+            network: "sepolia".to_string(),
+            other: other.to_string(),
+        };
+        assert_eq!(
+            issues,
+            vec![
+                ValidationIssue::InvalidTimestamp {
+                    network: None,
+                    field: "updatedAt",
+                    value: "2025-13-01T00:00:00Z".to_string(),
+                },
+                ValidationIssue::DuplicateId { id: "sepolia".to_string() },
+                alias_collision("eth", "mainnet"),
+                alias_collision("mainnet", "mainnet"),
+                ValidationIssue::DuplicateCaip2Id {
+                    caip2_id: "eip155:1".to_string(),
+                    network: "sepolia".to_string(),
+                    other: "mainnet".to_string(),
+                },
+                ValidationIssue::MalformedCaip2Id {
+                    network: "sepolia".to_string(),
+                    caip2_id: "EIP155 11155111".to_string(),
+                },
+                ValidationIssue::InvalidTimestamp {
+                    network: Some("mainnet".to_string()),
+                    field: "firehose.deprecatedAt",
+                    value: "2025-02-30T00:00:00Z".to_string(),
+                },
+                ValidationIssue::UnresolvedRelation {
+                    network: "sepolia".to_string(),
+                    kind: RelationKind::L2Of,
+                    target: "goerli".to_string(),
+                },
+                ValidationIssue::UnresolvedTokenApiNetwork {
+                    network: "sepolia".to_string(),
+                    network_id: "sepolia-testnet".to_string(),
+                },
+                ValidationIssue::InvalidTimestamp {
+                    network: Some("sepolia".to_string()),
+                    field: "graphNode.deprecatedAt",
+                    value: "yesterday".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            issues[2].to_string(),
+            "alias \"eth\" of network \"sepolia\" collides with network \"mainnet\""
+        );
+    }
+
+    #[test]
+    fn test_validate_sample_registry() {
+        let registry = NetworksRegistry::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/../../sample/TheGraphNetworksRegistry.json"))
+            .expect("Failed to load sample registry");
+        assert_eq!(registry.validate(), vec![]);
+    }
+}
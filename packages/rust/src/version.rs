@@ -1,68 +1,344 @@
-use std::cell::RefCell;
-use std::thread_local;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
 
-thread_local! {
-    static REGISTRY_BASE_URL: RefCell<String> = RefCell::new("https://networks-registry.thegraph.com".to_string());
-    static FALLBACK_BASE_URL: RefCell<String> = RefCell::new("https://raw.githubusercontent.com/graphprotocol/networks-registry/refs/heads/main/public".to_string());
+use crate::error::Error;
+use crate::types::NetworksRegistry;
+
+/// Base URL of the registry published at networks-registry.thegraph.com
+pub(crate) const DEFAULT_PRIMARY_BASE_URL: &str = "https://networks-registry.thegraph.com";
+
+/// Base URL of the registry files in the networks-registry GitHub repository
+pub(crate) const DEFAULT_FALLBACK_BASE_URL: &str =
+    "https://raw.githubusercontent.com/graphprotocol/networks-registry/refs/heads/main/public";
+
+/// Lists the files under `DEFAULT_FALLBACK_BASE_URL`, which has no directory listing of its own
+#[cfg(any(feature = "fetch", feature = "blocking"))]
+pub(crate) const DEFAULT_FALLBACK_LISTING_URL: &str = "https://api.github.com/repos/graphprotocol/networks-registry/contents/public";
+
+/// Prefix of the published registry file names, e.g. TheGraphNetworksRegistry_v0_7_0.json
+const FILE_NAME_PREFIX: &str = "TheGraphNetworksRegistry_v";
+
+/// Major and minor version of the registry schema this crate supports, `{major}_{minor}`.
+/// Updated by generate.sh along with the types.
+pub(crate) const SCHEMA_VERSION: &str = "0_7";
+
+/// Major and minor version of the registry JSON schema, e.g. 0.7
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SchemaVersion {
+    pub major: u32,
+    pub minor: u32,
 }
 
-fn get_registry_base_url() -> String {
-    REGISTRY_BASE_URL.with(|url| url.borrow().clone())
+/// Version number of a published registry, e.g. 0.7.0
+///
+/// Parses from `0.7.0` or `v0.7.0` and orders like semver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RegistryVersionNumber {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
 }
 
-fn get_fallback_base_url() -> String {
-    FALLBACK_BASE_URL.with(|url| url.borrow().clone())
+/// How the schema version of a loaded registry relates to the one this crate supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Same major and minor schema version as this crate
+    Compatible,
+    /// Older schema than this crate supports, fields this crate expects may be missing
+    Older(SchemaVersion),
+    /// Newer schema than this crate supports, fields added since are silently dropped
+    Newer(SchemaVersion),
+    /// The schema version could not be determined from the document
+    Unknown,
 }
 
-#[cfg(test)]
-pub fn set_base_urls(base_url: &str, fallback_url: &str) {
-    REGISTRY_BASE_URL.with(|cell| cell.replace(base_url.to_string()));
-    FALLBACK_BASE_URL.with(|cell| cell.replace(fallback_url.to_string()));
+impl SchemaVersion {
+    /// Returns the schema version this crate was generated from
+    pub fn supported() -> Self {
+        let (major, minor) = SCHEMA_VERSION.split_once('_').expect("schema version is {major}_{minor}");
+        SchemaVersion {
+            major: major.parse().expect("major schema version is a number"),
+            minor: minor.parse().expect("minor schema version is a number"),
+        }
+    }
+
+    /// Returns the URL of this schema version, e.g.
+    /// https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_v0_7.json
+    pub fn schema_url(&self) -> String {
+        format!(
+            "{}/TheGraphNetworksRegistrySchema_v{}_{}.json",
+            DEFAULT_PRIMARY_BASE_URL, self.major, self.minor
+        )
+    }
+
+    /// Parses the schema version from a schema URL, e.g.
+    /// https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_v0_7.json
+    pub fn from_schema_url(url: &str) -> Option<Self> {
+        let file_name = url.rsplit('/').next()?.strip_suffix(".json")?;
+        let (major, minor) = file_name.rsplit_once("_v")?.1.split_once('_')?;
+        Some(SchemaVersion {
+            major: major.parse().ok()?,
+            minor: minor.parse().ok()?,
+        })
+    }
+
+    /// Parses the schema version from the major and minor parts of a registry version, e.g. 0.7.0
+    pub fn from_registry_version(version: &str) -> Option<Self> {
+        let mut parts = version.trim_start_matches('v').split('.');
+        let (Some(major), Some(minor)) = (parts.next(), parts.next()) else {
+            return None;
+        };
+        Some(SchemaVersion {
+            major: major.parse().ok()?,
+            minor: minor.parse().ok()?,
+        })
+    }
 }
 
-pub(crate) const SCHEMA_VERSION: &str = env!("CARGO_PKG_VERSION_MAJOR_MINOR");
+impl std::fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl RegistryVersionNumber {
+    /// Creates a version number from its parts
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        RegistryVersionNumber { major, minor, patch }
+    }
+
+    /// Returns the schema version this crate was generated from, with patch version 0
+    pub fn supported() -> Self {
+        SchemaVersion::supported().into()
+    }
+
+    /// Returns the schema version of this registry version, i.e. its major and minor version
+    pub fn schema_version(&self) -> SchemaVersion {
+        SchemaVersion {
+            major: self.major,
+            minor: self.minor,
+        }
+    }
+
+    /// Parses the version from a published file name, e.g. TheGraphNetworksRegistry_v0_7_0.json
+    ///
+    /// Returns `None` for other files, including the `_v0_7_x.json` latest version files.
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let version = file_name.strip_prefix(FILE_NAME_PREFIX)?.strip_suffix(".json")?;
+        let mut parts = version.split('_');
+        let (Some(major), Some(minor), Some(patch), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+            return None;
+        };
+        Some(RegistryVersionNumber {
+            major: parse_part(major).ok()?,
+            minor: parse_part(minor).ok()?,
+            patch: parse_part(patch).ok()?,
+        })
+    }
+
+    /// Returns the published file name of this version, e.g. TheGraphNetworksRegistry_v0_7_0.json
+    pub fn file_name(&self) -> String {
+        format!("{}{}_{}_{}.json", FILE_NAME_PREFIX, self.major, self.minor, self.patch)
+    }
+
+    /// Returns the newest version with the given major and minor version
+    pub fn latest_patch<'a>(versions: impl IntoIterator<Item = &'a Self>, major: u32, minor: u32) -> Option<Self> {
+        versions
+            .into_iter()
+            .filter(|version| version.major == major && version.minor == minor)
+            .max()
+            .copied()
+    }
+
+    /// Lists the versions of the registry files in a local directory of snapshots
+    ///
+    /// # Returns
+    ///
+    /// The versions found, oldest first. Files that aren't named like published versions are ignored
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read
+    pub fn list_dir(dir: impl AsRef<Path>) -> Result<Vec<Self>, Error> {
+        let mut versions = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            if let Some(version) = entry?.file_name().to_str().and_then(Self::from_file_name) {
+                versions.push(version);
+            }
+        }
+        versions.sort();
+        Ok(versions)
+    }
+
+    /// Finds the versions named in a directory listing, e.g. an HTML index or a JSON file list
+    ///
+    /// Returns them oldest first, without duplicates.
+    #[cfg(any(feature = "fetch", feature = "blocking"))]
+    pub(crate) fn find_in_listing(listing: &str) -> Vec<Self> {
+        let mut versions: Vec<Self> = listing
+            .match_indices(FILE_NAME_PREFIX)
+            .filter_map(|(start, _)| {
+                let rest = &listing[start..];
+                let end = rest.find(".json")? + ".json".len();
+                Self::from_file_name(&rest[..end])
+            })
+            .collect();
+        versions.sort();
+        versions.dedup();
+        versions
+    }
+}
+
+impl FromStr for RegistryVersionNumber {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason| Error::InvalidVersion {
+            value: value.to_string(),
+            reason,
+        };
+        let mut parts = value.strip_prefix('v').unwrap_or(value).split('.');
+        let (Some(major), Some(minor), Some(patch), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid("expected major.minor.patch, e.g. 0.7.0"));
+        };
+        Ok(RegistryVersionNumber {
+            major: parse_part(major).map_err(invalid)?,
+            minor: parse_part(minor).map_err(invalid)?,
+            patch: parse_part(patch).map_err(invalid)?,
+        })
+    }
+}
+
+impl From<SchemaVersion> for RegistryVersionNumber {
+    fn from(version: SchemaVersion) -> Self {
+        RegistryVersionNumber::new(version.major, version.minor, 0)
+    }
+}
+
+impl fmt::Display for RegistryVersionNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+fn parse_part(part: &str) -> Result<u32, &'static str> {
+    if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+        return Err("version parts must be numbers");
+    }
+    part.parse().map_err(|_| "version part is too large")
+}
+
+impl Compatibility {
+    /// Returns true if the document has the schema version this crate supports
+    pub fn is_compatible(&self) -> bool {
+        *self == Compatibility::Compatible
+    }
+}
 
 #[derive(Debug, Clone, Copy)]
 pub enum RegistryVersion<'a> {
     /// Latest compatible version (v{major}.{minor}.x)
     Latest,
-    /// Specific version (e.g., v0.5.3)
+    /// Specific version (e.g., v0.5.3), checked with [`RegistryVersion::number`] before fetching
     Exact(&'a str),
+    /// Specific version number
+    Number(RegistryVersionNumber),
 }
 
-impl<'a> RegistryVersion<'a> {
+impl From<RegistryVersionNumber> for RegistryVersion<'_> {
+    fn from(version: RegistryVersionNumber) -> Self {
+        RegistryVersion::Number(version)
+    }
+}
+
+impl RegistryVersion<'_> {
+    /// Returns the version number of a specific version, or `None` for the latest version
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidVersion` if an `Exact` version is not a valid version number, e.g. `0.5` or `v0.5.x`
+    pub fn number(&self) -> Result<Option<RegistryVersionNumber>, Error> {
+        match self {
+            RegistryVersion::Latest => Ok(None),
+            RegistryVersion::Exact(version) => version.parse().map(Some),
+            RegistryVersion::Number(version) => Ok(Some(*version)),
+        }
+    }
+
     pub fn get_url(&self) -> String {
         self.get_primary_url()
     }
 
     pub fn get_primary_url(&self) -> String {
-        match self {
-            RegistryVersion::Latest => {
-                format!("{}/TheGraphNetworksRegistry_v{}_x.json", get_registry_base_url(), SCHEMA_VERSION)
-            }
-            RegistryVersion::Exact(version) => {
-                format!(
-                    "{}/TheGraphNetworksRegistry_{}.json",
-                    get_registry_base_url(),
-                    version.replace('.', "_")
-                )
-            }
-        }
+        self.get_url_with_base(DEFAULT_PRIMARY_BASE_URL)
     }
 
     pub fn get_fallback_url(&self) -> String {
+        self.get_url_with_base(DEFAULT_FALLBACK_BASE_URL)
+    }
+
+    /// Returns the URL of this version under `base_url`, e.g. a mirror of the registry
+    pub fn get_url_with_base(&self, base_url: &str) -> String {
+        format!("{}/{}", base_url.trim_end_matches('/'), self.file_name())
+    }
+
+    /// Returns the file name of this version, e.g. TheGraphNetworksRegistry_v0_5_3.json
+    pub fn file_name(&self) -> String {
         match self {
-            RegistryVersion::Latest => {
-                format!("{}/TheGraphNetworksRegistry_v{}_x.json", get_fallback_base_url(), SCHEMA_VERSION)
-            }
-            RegistryVersion::Exact(version) => {
-                format!(
-                    "{}/TheGraphNetworksRegistry_{}.json",
-                    get_fallback_base_url(),
-                    version.replace('.', "_")
-                )
-            }
+            RegistryVersion::Latest => format!("TheGraphNetworksRegistry_v{}_x.json", SCHEMA_VERSION),
+            RegistryVersion::Exact(version) => match version.parse::<RegistryVersionNumber>() {
+                Ok(version) => version.file_name(),
+                Err(_) => format!("TheGraphNetworksRegistry_{}.json", version.replace('.', "_")),
+            },
+            RegistryVersion::Number(version) => version.file_name(),
+        }
+    }
+}
+
+impl NetworksRegistry {
+    /// Returns the schema version of the registry, taken from the `$schema` URL,
+    /// or from the `version` field if the URL doesn't contain one
+    pub fn schema_version(&self) -> Option<SchemaVersion> {
+        SchemaVersion::from_schema_url(&self.schema).or_else(|| SchemaVersion::from_registry_version(&self.version))
+    }
+
+    /// Parses the `version` field of the registry
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidVersion` if the field is not a valid version number
+    pub fn version_number(&self) -> Result<RegistryVersionNumber, Error> {
+        self.version.parse()
+    }
+
+    /// Compares the schema version of the registry with the one this crate supports
+    pub fn compatibility(&self) -> Compatibility {
+        let supported = SchemaVersion::supported();
+        match self.schema_version() {
+            Some(version) if version == supported => Compatibility::Compatible,
+            Some(version) if version < supported => Compatibility::Older(version),
+            Some(version) => Compatibility::Newer(version),
+            None => Compatibility::Unknown,
+        }
+    }
+
+    /// Checks that the registry has the schema version this crate supports
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::SchemaVersionMismatch` if the schema version is different or unknown
+    pub fn ensure_compatible(&self) -> Result<(), Error> {
+        if self.compatibility().is_compatible() {
+            return Ok(());
         }
+        Err(Error::SchemaVersionMismatch {
+            expected: SchemaVersion::supported().to_string(),
+            found: self
+                .schema_version()
+                .map_or_else(|| "unknown".to_string(), |version| version.to_string()),
+        })
     }
 }
 
@@ -75,24 +351,171 @@ mod tests {
         // Test primary URL
         assert_eq!(
             RegistryVersion::Latest.get_primary_url(),
-            format!("{}/TheGraphNetworksRegistry_v{}_x.json", get_registry_base_url(), SCHEMA_VERSION)
+            format!("{}/TheGraphNetworksRegistry_v{}_x.json", DEFAULT_PRIMARY_BASE_URL, SCHEMA_VERSION)
         );
 
         // Test fallback URL
         assert_eq!(
             RegistryVersion::Latest.get_fallback_url(),
-            format!("{}/TheGraphNetworksRegistry_v{}_x.json", get_fallback_base_url(), SCHEMA_VERSION)
+            format!("{}/TheGraphNetworksRegistry_v{}_x.json", DEFAULT_FALLBACK_BASE_URL, SCHEMA_VERSION)
         );
 
         // Test exact version URLs
         let version = "v0.5.0";
         assert_eq!(
             RegistryVersion::Exact(version).get_primary_url(),
-            format!("{}/TheGraphNetworksRegistry_v0_5_0.json", get_registry_base_url())
+            format!("{}/TheGraphNetworksRegistry_v0_5_0.json", DEFAULT_PRIMARY_BASE_URL)
         );
         assert_eq!(
             RegistryVersion::Exact(version).get_fallback_url(),
-            format!("{}/TheGraphNetworksRegistry_v0_5_0.json", get_fallback_base_url())
+            format!("{}/TheGraphNetworksRegistry_v0_5_0.json", DEFAULT_FALLBACK_BASE_URL)
+        );
+
+        // Test typed and unprefixed version numbers
+        let number = RegistryVersionNumber::new(0, 5, 0);
+        assert_eq!(
+            RegistryVersion::from(number).get_primary_url(),
+            format!("{}/TheGraphNetworksRegistry_v0_5_0.json", DEFAULT_PRIMARY_BASE_URL)
+        );
+        assert_eq!(RegistryVersion::Exact("0.5.0").file_name(), "TheGraphNetworksRegistry_v0_5_0.json");
+        assert_eq!(RegistryVersion::Exact(version).number().unwrap(), Some(number));
+        assert_eq!(RegistryVersion::Latest.number().unwrap(), None);
+        assert!(matches!(
+            RegistryVersion::Exact("v0.5.x").number(),
+            Err(Error::InvalidVersion { value, .. }) if value == "v0.5.x"
+        ));
+
+        // Test custom base URLs
+        assert_eq!(
+            RegistryVersion::Exact(version).get_url_with_base("https://mirror.example.com/registry/"),
+            "https://mirror.example.com/registry/TheGraphNetworksRegistry_v0_5_0.json"
+        );
+    }
+
+    #[test]
+    fn test_registry_version_number() {
+        let version: RegistryVersionNumber = "0.7.0".parse().unwrap();
+        assert_eq!(version, RegistryVersionNumber::new(0, 7, 0));
+        assert_eq!("v0.10.2".parse::<RegistryVersionNumber>().unwrap().to_string(), "0.10.2");
+        assert!(RegistryVersionNumber::new(0, 10, 0) > RegistryVersionNumber::new(0, 9, 12));
+        for invalid in ["0.5", "v0.5.x", "0.5.3.1", "0..1", "0.5.-1", " 0.5.1", "0.5.99999999999"] {
+            assert!(
+                matches!(invalid.parse::<RegistryVersionNumber>(), Err(Error::InvalidVersion { value, .. }) if value == invalid),
+                "{} should not parse",
+                invalid
+            );
+        }
+
+        assert_eq!(
+            RegistryVersionNumber::from_file_name("TheGraphNetworksRegistry_v0_7_0.json"),
+            Some(RegistryVersionNumber::new(0, 7, 0))
+        );
+        assert_eq!(RegistryVersionNumber::from_file_name("TheGraphNetworksRegistry_v0_7_x.json"), None);
+        assert_eq!(
+            RegistryVersionNumber::new(0, 7, 0).file_name(),
+            "TheGraphNetworksRegistry_v0_7_0.json"
+        );
+
+        let supported = RegistryVersionNumber::supported();
+        assert_eq!(supported.schema_version(), SchemaVersion::supported());
+        assert_eq!(supported.patch, 0);
+
+        let versions = [0, 10, 1].map(|patch| RegistryVersionNumber::new(0, 6, patch));
+        assert_eq!(
+            RegistryVersionNumber::latest_patch(&versions, 0, 6),
+            Some(RegistryVersionNumber::new(0, 6, 10))
+        );
+        assert_eq!(RegistryVersionNumber::latest_patch(&versions, 0, 7), None);
+
+        let dir = std::env::temp_dir().join(format!("graph-networks-registry-versions-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for file_name in [
+            "TheGraphNetworksRegistry_v0_6_0.json",
+            "TheGraphNetworksRegistry_v0_5_9.json",
+            "notes.txt",
+        ] {
+            fs::write(dir.join(file_name), "{}").unwrap();
+        }
+        assert_eq!(
+            RegistryVersionNumber::list_dir(&dir).unwrap(),
+            vec![RegistryVersionNumber::new(0, 5, 9), RegistryVersionNumber::new(0, 6, 0)]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(RegistryVersionNumber::list_dir(&dir), Err(Error::Io(_))));
+    }
+
+    #[cfg(any(feature = "fetch", feature = "blocking"))]
+    #[test]
+    fn test_find_versions_in_listing() {
+        let listing = r#"<a href="TheGraphNetworksRegistry_v0_6_1.json">TheGraphNetworksRegistry_v0_6_1.json</a>
+            <a href="/TheGraphNetworksRegistry_v0_7_x.json">latest</a>
+            <a href="TheGraphNetworksRegistry_v0_6_10.json">
+            {"name": "TheGraphNetworksRegistry_v0_5_3.json"}
+            <a href="TheGraphNetworksRegistrySchema_v0_6.json">"#;
+        assert_eq!(
+            RegistryVersionNumber::find_in_listing(listing),
+            vec![
+                RegistryVersionNumber::new(0, 5, 3),
+                RegistryVersionNumber::new(0, 6, 1),
+                RegistryVersionNumber::new(0, 6, 10),
+            ]
+        );
+        assert!(RegistryVersionNumber::find_in_listing("<html>Not Found</html>").is_empty());
+    }
+
+    #[test]
+    fn test_compatibility() {
+        let supported = SchemaVersion::supported();
+        assert_eq!(supported.to_string(), SCHEMA_VERSION.replace('_', "."));
+        assert_eq!(
+            SchemaVersion::from_schema_url("https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_v0_5.json"),
+            Some(SchemaVersion { major: 0, minor: 5 })
         );
+        assert_eq!(SchemaVersion::from_schema_url(&supported.schema_url()), Some(supported));
+        assert_eq!(
+            SchemaVersion::from_schema_url("https://example.com/TheGraphNetworksRegistrySchema_vx_x.json"),
+            None
+        );
+
+        let registry_json = |schema: &str, version: &str| {
+            format!(
+                r#"{{"$schema": "{}", "version": "{}", "title": "", "description": "", "updatedAt": "2025-01-01T00:00:00Z", "networks": []}}"#,
+                schema, version
+            )
+        };
+        let schema_url = |major: u32, minor: u32| {
+            format!(
+                "https://networks-registry.thegraph.com/TheGraphNetworksRegistrySchema_v{}_{}.json",
+                major, minor
+            )
+        };
+
+        let registry = NetworksRegistry::from_json(&registry_json(&schema_url(supported.major, supported.minor), "x.x.x")).unwrap();
+        assert_eq!(registry.compatibility(), Compatibility::Compatible);
+        assert!(registry.ensure_compatible().is_ok());
+
+        let newer = SchemaVersion {
+            major: supported.major,
+            minor: supported.minor + 1,
+        };
+        let registry = NetworksRegistry::from_json(&registry_json(&schema_url(newer.major, newer.minor), "x.x.x")).unwrap();
+        assert_eq!(registry.compatibility(), Compatibility::Newer(newer));
+        assert!(matches!(
+            registry.ensure_compatible(),
+            Err(Error::SchemaVersionMismatch { expected, found }) if expected == supported.to_string() && found == newer.to_string()
+        ));
+
+        // falls back to the registry version when the schema URL has none
+        let registry = NetworksRegistry::from_json(&registry_json("https://example.com/schema.json", "0.1.3")).unwrap();
+        assert_eq!(registry.compatibility(), Compatibility::Older(SchemaVersion { major: 0, minor: 1 }));
+        assert_eq!(registry.version_number().unwrap(), RegistryVersionNumber::new(0, 1, 3));
+
+        let registry = NetworksRegistry::from_json(&registry_json("https://example.com/schema.json", "x.x.x")).unwrap();
+        assert_eq!(registry.compatibility(), Compatibility::Unknown);
+        assert!(matches!(registry.version_number(), Err(Error::InvalidVersion { .. })));
+        assert!(matches!(
+            registry.ensure_compatible(),
+            Err(Error::SchemaVersionMismatch { found, .. }) if found == "unknown"
+        ));
     }
 }